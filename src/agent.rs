@@ -1,79 +1,123 @@
 //! Agent information module
 
-use reqwest;
-use serde_json;
+use serde::{Deserialize, Serialize};
+
+use crate::client::ApiClient;
+use crate::error::SpaceTradersError;
+use crate::models::{Agent, ApiResponse, Ship, ShipNav, system_symbol_of};
 
 /// Structure to hold agent data
-#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct AgentInfo {
+    pub account_id: Option<String>,
     pub symbol: String,
-    pub faction: String,
+    pub starting_faction: String,
     pub credits: i64,
     pub headquarters: String,
-    pub system_symbol: Option<String>,
+    pub ship_count: u32,
+}
+
+impl AgentInfo {
+    /// System the agent's headquarters waypoint is in
+    ///
+    /// The agent endpoint has no location of its own, so this is parsed from
+    /// the `headquarters` waypoint symbol.
+    pub fn headquarters_system(&self) -> String {
+        system_symbol_of(&self.headquarters)
+    }
+}
+
+impl From<Agent> for AgentInfo {
+    fn from(agent: Agent) -> Self {
+        Self {
+            account_id: agent.account_id,
+            symbol: agent.symbol,
+            starting_faction: agent.starting_faction,
+            credits: agent.credits,
+            headquarters: agent.headquarters,
+            ship_count: agent.ship_count,
+        }
+    }
 }
 
 /// Gets agent information from the SpaceTraders API
 pub async fn get_agent_info(
-    client: &reqwest::Client,
-    token: &str
-) -> Result<AgentInfo, Box<dyn std::error::Error>> {
+    client: &ApiClient
+) -> Result<AgentInfo, SpaceTradersError> {
     println!("\n=== Getting Agent Information ===");
-    
-    let url = "https://api.spacetraders.io/v2/my/agent";
-    
-    let response = client
-        .get(url)
-        .header("Authorization", format!("Bearer {}", token))
-        .send()
-        .await?;
-        
-    println!("Response status: {}", response.status());
-    
-    let raw_text = response.text().await?;
-    println!("Raw agent data: {}...", &raw_text[..std::cmp::min(200, raw_text.len())]);
-    
-    // Try to parse the response
-    let agent_info = match serde_json::from_str::<serde_json::Value>(&raw_text) {
-        Ok(value) => {
-            let data = value.get("data").unwrap_or(&serde_json::Value::Null);
-            
-            println!("\n=== Parsed Agent Information ===");
-            let symbol = data.get("symbol").and_then(|s| s.as_str()).unwrap_or("").to_string();
-            let faction = data.get("startingFaction").and_then(|s| s.as_str()).unwrap_or("").to_string();
-            let credits = data.get("credits").and_then(|c| c.as_i64()).unwrap_or(0);
-            let headquarters = data.get("headquarters").and_then(|s| s.as_str()).unwrap_or("").to_string();
-            
-            // Try to get system_symbol from the agent's location
-            let system_symbol = data.get("location").and_then(|l| l.get("systemSymbol")).and_then(|s| s.as_str()).map(|s| s.to_string());
-            
-            println!("Symbol: {}", symbol);
-            println!("Faction: {}", faction);
-            println!("Credits: {}", credits);
-            println!("Headquarters: {}", headquarters);
-            if let Some(system) = &system_symbol {
-                println!("Current System: {}", system);
-            }
-            
-            AgentInfo {
-                symbol,
-                faction,
-                credits,
-                headquarters,
-                system_symbol,
-            }
-        }
-        Err(e) => {
-            println!("\nError parsing agent data: {:?}", e);
-            AgentInfo {
-                symbol: "".to_string(),
-                faction: "".to_string(),
-                credits: 0,
-                headquarters: "".to_string(),
-                system_symbol: None,
-            }
-        }
-    };
-    
+
+    let response: ApiResponse<Agent> = client.get("/my/agent").await?;
+    let agent_info = AgentInfo::from(response.data);
+
+    println!("\n=== Parsed Agent Information ===");
+    println!("Symbol: {}", agent_info.symbol);
+    println!("Faction: {}", agent_info.starting_faction);
+    println!("Credits: {}", agent_info.credits);
+    println!("Headquarters: {}", agent_info.headquarters);
+    println!("Ships: {}", agent_info.ship_count);
+
     Ok(agent_info)
 }
+
+/// Gets the navigation state of the agent's first ship, if it has any
+///
+/// Agents have no position of their own; their ships do.
+pub async fn fleet_location(
+    client: &ApiClient
+) -> Result<Option<ShipNav>, SpaceTradersError> {
+    let mut pages = client.paginate::<Ship>("/my/ships");
+    let first_ship = pages.next_page().await?.and_then(|ships| ships.into_iter().next());
+    Ok(first_ship.map(|ship| ship.nav))
+}
+
+/// Lists every public agent, across all pages
+pub async fn list_agents(
+    client: &ApiClient
+) -> Result<Vec<Agent>, SpaceTradersError> {
+    client.get_all("/agents").await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_server::{MockResponse, MockServer};
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_get_agent_info_from_local_server() {
+        let server = MockServer::start().await;
+        server.route("GET", "/my/agent", vec![MockResponse::json(200, json!({
+            "data": {
+                "accountId": "account-1",
+                "symbol": "AGENT-123",
+                "headquarters": "X1-ABCD-1234",
+                "credits": 175000,
+                "startingFaction": "COSMIC",
+                "shipCount": 2
+            }
+        }))]);
+
+        let client = ApiClient::with_base_url(server.base_url(), Some("test_token"));
+        let agent = get_agent_info(&client).await.unwrap();
+
+        assert_eq!(agent.account_id.as_deref(), Some("account-1"));
+        assert_eq!(agent.symbol, "AGENT-123");
+        assert_eq!(agent.starting_faction, "COSMIC");
+        assert_eq!(agent.credits, 175000);
+        assert_eq!(agent.ship_count, 2);
+        assert_eq!(agent.headquarters_system(), "X1-ABCD");
+    }
+
+    #[tokio::test]
+    async fn test_fleet_location_without_ships() {
+        let server = MockServer::start().await;
+        server.route("GET", "/my/ships", vec![MockResponse::json(200, json!({
+            "data": [],
+            "meta": {"total": 0, "page": 1, "limit": 20}
+        }))]);
+
+        let client = ApiClient::with_base_url(server.base_url(), Some("test_token"));
+
+        assert!(fleet_location(&client).await.unwrap().is_none());
+    }
+}
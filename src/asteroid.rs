@@ -1,7 +1,11 @@
 //! Asteroid scanning module for finding materials
 
-use reqwest;
-use serde_json;
+use crate::agent::fleet_location;
+use crate::client::ApiClient;
+use crate::error::SpaceTradersError;
+use crate::models::{ApiResponse, SurveyResult, Waypoint};
+use crate::shared_storage::SharedStatusStorage;
+use crate::universe::{UniverseCache, system_waypoints};
 
 /// Structure to hold asteroid information
 #[derive(Debug)]
@@ -12,284 +16,141 @@ pub struct AsteroidInfo {
     pub materials: Vec<String>,
 }
 
-/// Structure to hold survey information
-#[derive(Debug, Clone)]
-pub struct SurveyInfo {
-    pub symbol: String,
-    pub deposits: Vec<String>,
-    pub expiration: u64, // Unix timestamp when survey expires
-    pub size: SurveySize,
-}
-
-/// Size of a survey
-#[derive(Debug, Clone)]
-pub enum SurveySize {
-    Small,
-    Medium,
-    Large,
-}
-
 /// Finds asteroids in a system that contain specific materials
+///
+/// Waypoints come from the universe cache; the system is charted on first use.
 pub async fn scan_for_asteroids_with_materials(
-    client: &reqwest::Client,
-    token: &str,
+    client: &ApiClient,
+    universe: &mut UniverseCache,
     system_symbol: &str,
     required_materials: &[&str]
-) -> Result<(), Box<dyn std::error::Error>> {
+) -> Result<Option<AsteroidInfo>, SpaceTradersError> {
     println!("\n=== Scanning for resource-rich asteroids ===");
     println!("System: {}", system_symbol);
     println!("Required materials: {:?}", required_materials);
 
-    // First get the system information to find waypoints
-    let system_url = format!("https://api.spacetraders.io/v2/systems/{}", system_symbol);
+    // List the system's waypoints; each one already carries its traits
+    let waypoint_array = system_waypoints(client, universe, system_symbol).await?.to_vec();
     
-    let response = client
-        .get(&system_url)
-        .header("Authorization", format!("Bearer {}", token))
-        .send()
-        .await?;
-        
-    println!("System response status: {}", response.status());
+    // Parse and look for asteroid waypoints
+    println!("\nFound {} waypoints in system", waypoint_array.len());
     
-    let system_text = response.text().await?;
+    // Collect all asteroids with their coordinates and materials
+    let mut asteroids: Vec<AsteroidInfo> = Vec::new();
     
-    // Parse and look for asteroid waypoints
-    match serde_json::from_str::<serde_json::Value>(&system_text) {
-        Ok(system_value) => {
-            if let Some(waypoints) = system_value.get("data").and_then(|d| d.get("waypoints")) {
-                if let Some(waypoint_array) = waypoints.as_array() {
-                    println!("\nFound {} waypoints in system", waypoint_array.len());
-                    
-                    // Collect all asteroids with their coordinates and materials
-                    let mut asteroids: Vec<AsteroidInfo> = Vec::new();
-                    
-                    for waypoint in waypoint_array {
-                        if let Some(waypoint_type) = waypoint.get("type") {
-                            // Look for asteroid-related waypoints
-                            if let Some(type_str) = waypoint_type.as_str() {
-                                if type_str.contains("ASTEROID") || type_str == "ASTEROID_FIELD" {
-                                    let waypoint_symbol = waypoint.get("symbol").and_then(|s| s.as_str()).unwrap_or("Unknown");
-                                    println!("\nFound asteroid waypoint: {}", waypoint_symbol);
-                                    
-                                    // Get detailed information about this asteroid
-                                    if let Ok(asteroid_info) = check_asteroid_details(client, token, waypoint_symbol).await {
-                                        // Check if this asteroid has the required materials
-                                        let mut found_materials = Vec::new();
-                                        for &material in required_materials {
-                                            if asteroid_info.materials.iter().any(|m| m.contains(material) || material.contains(m.as_str())) {
-                                                found_materials.push(material.to_string());
-                                            }
-                                        }
-                                        
-                                        if !found_materials.is_empty() {
-                                            println!("  Found materials: {:?}", found_materials);
-                                            asteroids.push(asteroid_info);
-                                        } else {
-                                            println!("  No matching materials found");
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    
-                    // If we found asteroids with required materials, find the closest one
-                    if !asteroids.is_empty() {
-                        println!("\n=== Finding Closest Asteroid ===");
-                        
-                        // Get current agent position
-                        let (current_x, current_y) = get_agent_position(client, token).await?;
-                        
-                        // Find the closest asteroid
-                        let closest_asteroid = find_closest_asteroid(&asteroids, current_x, current_y);
-                        
-                        if let Some(asteroid) = closest_asteroid {
-                            println!("Closest asteroid with required materials:");
-                            println!("  Symbol: {}", asteroid.symbol);
-                            println!("  Coordinates: ({}, {})", asteroid.x, asteroid.y);
-                            println!("  Distance from current position: {:.2} units", 
-                                calculate_distance(current_x, current_y, asteroid.x, asteroid.y));
-                            println!("  Materials: {:?}", asteroid.materials);
-                        } else {
-                            println!("No asteroids with required materials found");
-                        }
-                    } else {
-                        println!("\nNo asteroids with required materials found in this system");
-                    }
-                }
+    for waypoint in waypoint_array {
+        // Look for asteroid-related waypoints
+        if !waypoint.waypoint_type.contains("ASTEROID") {
+            continue;
+        }
+        println!("\nFound asteroid waypoint: {}", waypoint.symbol);
+        
+        let asteroid_info = asteroid_details(waypoint);
+        
+        // Check if this asteroid has the required materials
+        let mut found_materials = Vec::new();
+        for &material in required_materials {
+            if asteroid_info.materials.iter().any(|m| m.contains(material) || material.contains(m.as_str())) {
+                found_materials.push(material.to_string());
             }
-        },
-        Err(e) => {
-            println!("Error parsing system data: {:?}", e);
+        }
+        
+        if !found_materials.is_empty() {
+            println!("  Found materials: {:?}", found_materials);
+            asteroids.push(asteroid_info);
+        } else {
+            println!("  No matching materials found");
         }
     }
-
-    Ok(())
-}
-
-/// Performs a survey on an asteroid waypoint
-pub async fn survey_asteroid(
-    client: &reqwest::Client,
-    token: &str,
-    waypoint_symbol: &str
-) -> Result<SurveyInfo, Box<dyn std::error::Error>> {
-    println!("\n=== Surveying asteroid ===");
-    println!("Waypoint: {}", waypoint_symbol);
-    
-    // Create the survey request
-    let survey_url = format!("https://api.spacetraders.io/v2/waypoints/{}/survey", waypoint_symbol);
     
-    let response = client
-        .post(&survey_url)
-        .header("Authorization", format!("Bearer {}", token))
-        .send()
-        .await?;
-        
-    println!("Survey response status: {}", response.status());
+    // If we found asteroids with required materials, find the closest one
+    if asteroids.is_empty() {
+        println!("\nNo asteroids with required materials found in this system");
+        return Ok(None);
+    }
     
-    let survey_text = response.text().await?;
+    println!("\n=== Finding Closest Asteroid ===");
     
-    // Parse the survey data
-    match serde_json::from_str::<serde_json::Value>(&survey_text) {
-        Ok(survey_value) => {
-            if let Some(data) = survey_value.get("data") {
-                // Get the survey information
-                if let Some(survey) = data.get("survey") {
-                    // Parse the survey details
-                    let symbol = survey.get("symbol").and_then(|s| s.as_str()).unwrap_or("").to_string();
-                    let expiration = survey.get("expiration").and_then(|e| e.as_u64()).unwrap_or(0);
-                    
-                    // Parse deposits
-                    let mut deposits = Vec::new();
-                    if let Some(deposit_array) = survey.get("deposits").and_then(|d| d.as_array()) {
-                        for deposit in deposit_array {
-                            if let Some(deposit_symbol) = deposit.get("symbol").and_then(|s| s.as_str()) {
-                                deposits.push(deposit_symbol.to_string());
-                            }
-                        }
-                    }
-                    
-                    // Parse size
-                    let size = match survey.get("size").and_then(|s| s.as_str()) {
-                        Some("SMALL") => SurveySize::Small,
-                        Some("MEDIUM") => SurveySize::Medium,
-                        Some("LARGE") => SurveySize::Large,
-                        _ => SurveySize::Small, // Default to small if unknown
-                    };
-                    
-                    let survey_info = SurveyInfo {
-                        symbol,
-                        deposits,
-                        expiration,
-                        size
-                    };
-                    
-                    println!("Survey completed successfully:");
-                    println!("  Symbol: {}", survey_info.symbol);
-                    println!("  Deposits: {:?}", survey_info.deposits);
-                    println!("  Size: {:?}", survey_info.size);
-                    println!("  Expiration: {}", survey_info.expiration);
-                    
-                    Ok(survey_info)
-                } else {
-                    Err("Could not parse survey data".into())
-                }
-            } else {
-                Err("Could not find survey data in response".into())
-            }
-        },
+    // Get current agent position
+    let (current_x, current_y) = match get_agent_position(client).await {
+        Ok(position) => position,
         Err(e) => {
-            println!("Error parsing survey data: {:?}", e);
-            Err("Could not parse survey response".into())
+            println!("Could not determine current position: {}", e);
+            return Ok(asteroids.into_iter().next());
         }
-    }
+    };
+    
+    // Find the closest asteroid
+    let closest_index = find_closest_asteroid(&asteroids, current_x, current_y)
+        .and_then(|closest| asteroids.iter().position(|a| a.symbol == closest.symbol));
+    let Some(index) = closest_index else {
+        println!("No asteroids with required materials found");
+        return Ok(None);
+    };
+    let asteroid = asteroids.swap_remove(index);
+    
+    println!("Closest asteroid with required materials:");
+    println!("  Symbol: {}", asteroid.symbol);
+    println!("  Coordinates: ({}, {})", asteroid.x, asteroid.y);
+    println!("  Distance from current position: {:.2} units", 
+        calculate_distance(current_x, current_y, asteroid.x, asteroid.y));
+    println!("  Materials: {:?}", asteroid.materials);
+
+    Ok(Some(asteroid))
 }
 
-/// Get the agent's current position
-pub async fn get_agent_position(
-    client: &reqwest::Client,
-    token: &str
-) -> Result<(i32, i32), Box<dyn std::error::Error>> {
-    // Get agent info to get current location
-    let url = "https://api.spacetraders.io/v2/my/agent";
+/// Surveys the asteroid a ship is orbiting and stores every survey found
+///
+/// A survey can return several deposits at once; every one of them is kept
+/// with the signed payload needed to extract against it.
+pub async fn survey_asteroid(
+    client: &ApiClient,
+    storage: &SharedStatusStorage,
+    ship_symbol: &str
+) -> Result<SurveyResult, SpaceTradersError> {
+    println!("\n=== Surveying asteroid ===");
+    println!("Ship: {}", ship_symbol);
     
-    let response = client
-        .get(url)
-        .header("Authorization", format!("Bearer {}", token))
-        .send()
-        .await?;
-        
-    let raw_text = response.text().await?;
+    let response: ApiResponse<SurveyResult> = client.post(&format!("/my/ships/{}/survey", ship_symbol)).await?;
+    let result = response.data;
     
-    // Parse the agent data to get location coordinates
-    if let Ok(agent_value) = serde_json::from_str::<serde_json::Value>(&raw_text) {
-        if let Some(data) = agent_value.get("data") {
-            // Try to get location coordinates
-            if let Some(location) = data.get("location") {
-                if let Some(x) = location.get("x").and_then(|v| v.as_i64()) {
-                    if let Some(y) = location.get("y").and_then(|v| v.as_i64()) {
-                        return Ok((x as i32, y as i32));
-                    }
-                }
-            }
-        }
+    println!("Survey completed successfully, {} surveys found:", result.surveys.len());
+    for survey in &result.surveys {
+        let deposits: Vec<&str> = survey.deposits.iter().map(|d| d.symbol.as_str()).collect();
+        println!("  {} at {}", survey.signature, survey.symbol);
+        println!("    Deposits: {:?}", deposits);
+        println!("    Size: {:?}", survey.size);
+        println!("    Expiration: {}", survey.expiration);
+        storage.update_survey(survey.clone()).await;
     }
+    println!("Survey cooldown: {} seconds", result.cooldown.remaining_seconds);
     
-    // If we can't get coordinates, return default (0, 0)
-    Ok((0, 0))
+    Ok(result)
 }
 
-/// Check asteroid details including coordinates and materials
-async fn check_asteroid_details(
-    client: &reqwest::Client,
-    token: &str,
-    waypoint_symbol: &str
-) -> Result<AsteroidInfo, Box<dyn std::error::Error>> {
-    // Get waypoint details to check if there are materials
-    let waypoint_url = format!("https://api.spacetraders.io/v2/waypoints/{}", waypoint_symbol);
+/// Get the agent's current position, taken from its first ship
+pub async fn get_agent_position(
+    client: &ApiClient
+) -> Result<(i32, i32), SpaceTradersError> {
+    let nav = fleet_location(client)
+        .await?
+        .ok_or_else(|| SpaceTradersError::UnexpectedResponse("agent has no ships".to_string()))?;
     
-    let response = client
-        .get(&waypoint_url)
-        .header("Authorization", format!("Bearer {}", token))
-        .send()
-        .await?;
-        
-    let waypoint_text = response.text().await?;
+    // Docked and orbiting ships sit at their route's destination
+    let position = &nav.route.destination;
+    Ok((position.x, position.y))
+}
+
+/// Builds asteroid details, including materials, from a listed waypoint
+fn asteroid_details(waypoint: Waypoint) -> AsteroidInfo {
+    // Get materials from traits
+    let materials = waypoint.traits.into_iter().map(|t| t.symbol).collect();
     
-    // Parse the waypoint data
-    match serde_json::from_str::<serde_json::Value>(&waypoint_text) {
-        Ok(waypoint_value) => {
-            if let Some(data) = waypoint_value.get("data") {
-                // Get coordinates
-                let x = data.get("x").and_then(|v| v.as_i64()).unwrap_or(0) as i32;
-                let y = data.get("y").and_then(|v| v.as_i64()).unwrap_or(0) as i32;
-                
-                // Get materials from traits
-                let mut materials = Vec::new();
-                if let Some(traits) = data.get("traits") {
-                    if let Some(traits_array) = traits.as_array() {
-                        for trait_value in traits_array {
-                            if let Some(trait_symbol) = trait_value.get("symbol").and_then(|s| s.as_str()) {
-                                materials.push(trait_symbol.to_string());
-                            }
-                        }
-                    }
-                }
-                
-                Ok(AsteroidInfo {
-                    symbol: waypoint_symbol.to_string(),
-                    x,
-                    y,
-                    materials
-                })
-            } else {
-                Err("Could not parse asteroid data".into())
-            }
-        },
-        Err(e) => {
-            println!("Error parsing waypoint data: {:?}", e);
-            Err("Could not parse asteroid details".into())
-        }
+    AsteroidInfo {
+        symbol: waypoint.symbol,
+        x: waypoint.x,
+        y: waypoint.y,
+        materials
     }
 }
 
@@ -308,4 +169,43 @@ fn find_closest_asteroid(asteroids: &[AsteroidInfo], current_x: i32, current_y:
             let dist_b = calculate_distance(current_x, current_y, b.x, b.y);
             dist_a.partial_cmp(&dist_b).unwrap_or(std::cmp::Ordering::Equal)
         })
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_server::{MockResponse, MockServer};
+    use crate::models::SurveySize;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_survey_asteroid_returns_every_survey() {
+        let survey = |signature: &str, size: &str| json!({
+            "signature": signature,
+            "symbol": "X1-DF55-A1",
+            "deposits": [{"symbol": "IRON_ORE"}, {"symbol": "IRON_ORE"}, {"symbol": "QUARTZ_SAND"}],
+            "expiration": "2026-10-16T12:34:56.789Z",
+            "size": size
+        });
+        let server = MockServer::start().await;
+        server.route("POST", "/my/ships/SHIP-1/survey", vec![MockResponse::json(201, json!({
+            "data": {
+                "cooldown": {"shipSymbol": "SHIP-1", "totalSeconds": 70, "remainingSeconds": 70, "expiration": "2026-10-16T12:01:10.000Z"},
+                "surveys": [survey("SIG-1", "SMALL"), survey("SIG-2", "MODERATE")]
+            }
+        }))]);
+
+        let client = ApiClient::with_base_url(server.base_url(), Some("test_token"));
+        let storage = SharedStatusStorage::default();
+        let result = survey_asteroid(&client, &storage, "SHIP-1").await.unwrap();
+
+        assert_eq!(result.surveys.len(), 2);
+        assert_eq!(result.surveys[1].signature, "SIG-2");
+        assert_eq!(result.surveys[1].size, SurveySize::Moderate);
+        assert_eq!(result.surveys[1].deposits.len(), 3);
+        assert_eq!(result.surveys[0].expires_at(), Some(1_792_154_096));
+        assert_eq!(result.cooldown.total_seconds, 70);
+        assert!(storage.read().await.get_survey("SIG-1").is_some());
+        assert!(storage.read().await.get_survey("SIG-2").is_some());
+    }
+}
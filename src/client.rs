@@ -0,0 +1,349 @@
+//! Shared HTTP client for the SpaceTraders API
+
+use std::sync::Arc;
+
+use reqwest::{Method, Response};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::error::SpaceTradersError;
+use crate::rate_limit::{RateLimitBudget, RateLimitConfig, RateLimiter};
+use crate::retry::{RetriesExhausted, RetryPolicy};
+
+/// Base URL of the public SpaceTraders v2 API
+pub const BASE_URL: &str = "https://api.spacetraders.io/v2";
+
+/// Client that owns the HTTP connection pool, the API base URL and the agent token
+///
+/// Clones share the same connection pool and rate limiter, so one client can be
+/// handed to every ship task.
+#[derive(Debug, Clone)]
+pub struct ApiClient {
+    http: reqwest::Client,
+    base_url: String,
+    token: Option<String>,
+    limiter: Arc<RateLimiter>,
+}
+
+impl ApiClient {
+    /// Creates a client authenticated with the given agent token
+    pub fn new(token: &str) -> Self {
+        Self::with_base_url(BASE_URL, Some(token))
+    }
+
+    /// Creates a client without a token, for endpoints such as registration
+    pub fn anonymous() -> Self {
+        Self::with_base_url(BASE_URL, None)
+    }
+
+    /// Creates a client that talks to another server, such as a local mock
+    pub fn with_base_url(base_url: &str, token: Option<&str>) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token: token.map(|t| t.to_string()),
+            limiter: Arc::new(RateLimiter::default()),
+        }
+    }
+
+    /// Replaces the rate limiter with one enforcing the given limits
+    pub fn with_rate_limit(mut self, config: RateLimitConfig) -> Self {
+        self.limiter = Arc::new(RateLimiter::new(config));
+        self
+    }
+
+    /// Returns the base URL every request path is appended to
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Returns the agent token, if this client is authenticated
+    pub fn token(&self) -> Option<&str> {
+        self.token.as_deref()
+    }
+
+    /// Returns the remaining request budget of the shared rate limiter
+    pub fn rate_limit_budget(&self) -> RateLimitBudget {
+        self.limiter.budget()
+    }
+
+    /// Builds the full URL for an API path such as `/my/agent`
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    /// Sends a request with the default retry policy for its method and returns the
+    /// raw response without checking its status
+    pub async fn send<B: Serialize + ?Sized>(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&B>
+    ) -> Result<Response, SpaceTradersError> {
+        let policy = RetryPolicy::for_method(&method);
+        self.send_with_retry(method, path, body, &policy).await
+    }
+
+    /// Sends a request, waiting on the rate limiter before every attempt and retrying
+    /// 429/5xx responses as the policy allows
+    pub async fn send_with_retry<B: Serialize + ?Sized>(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&B>,
+        policy: &RetryPolicy
+    ) -> Result<Response, SpaceTradersError> {
+        let mut attempt = 0;
+
+        loop {
+            attempt += 1;
+            self.limiter.acquire().await;
+
+            let mut request = self.http.request(method.clone(), self.url(path));
+
+            if let Some(token) = &self.token {
+                request = request.header("Authorization", format!("Bearer {}", token));
+            }
+
+            if let Some(body) = body {
+                request = request.json(body);
+            }
+
+            let delay = match request.send().await {
+                Ok(response) if policy.should_retry(response.status()) => {
+                    let status = response.status();
+                    if attempt >= policy.max_attempts {
+                        return Err(SpaceTradersError::RetriesExhausted(RetriesExhausted {
+                            method: method.to_string(),
+                            path: path.to_string(),
+                            attempts: attempt,
+                            status: Some(status.as_u16()),
+                            body: response.text().await.unwrap_or_default(),
+                        }));
+                    }
+                    let delay = policy.delay(attempt, response.headers());
+                    println!("{} {} returned {}, retrying in {:.2}s (attempt {}/{})",
+                        method, path, status, delay.as_secs_f64(), attempt, policy.max_attempts);
+                    delay
+                }
+                Ok(response) => return Ok(response),
+                Err(e) if policy.retry_server_errors && (e.is_connect() || e.is_timeout()) => {
+                    if attempt >= policy.max_attempts {
+                        return Err(SpaceTradersError::RetriesExhausted(RetriesExhausted {
+                            method: method.to_string(),
+                            path: path.to_string(),
+                            attempts: attempt,
+                            status: None,
+                            body: e.to_string(),
+                        }));
+                    }
+                    let delay = policy.backoff(attempt);
+                    println!("{} {} failed ({}), retrying in {:.2}s (attempt {}/{})",
+                        method, path, e, delay.as_secs_f64(), attempt, policy.max_attempts);
+                    delay
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    /// Sends a request with an explicit retry policy and deserializes the JSON response
+    pub async fn request<T: DeserializeOwned, B: Serialize + ?Sized>(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&B>,
+        policy: &RetryPolicy
+    ) -> Result<T, SpaceTradersError> {
+        let response = self.send_with_retry(method, path, body, policy).await?;
+        Self::parse(response).await
+    }
+
+    /// Sends a GET request and deserializes the JSON response
+    pub async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, SpaceTradersError> {
+        let response = self.send::<()>(Method::GET, path, None).await?;
+        Self::parse(response).await
+    }
+
+    /// Sends a POST request without a body and deserializes the JSON response
+    pub async fn post<T: DeserializeOwned>(&self, path: &str) -> Result<T, SpaceTradersError> {
+        let response = self.send::<()>(Method::POST, path, None).await?;
+        Self::parse(response).await
+    }
+
+    /// Sends a POST request with a JSON body and deserializes the JSON response
+    pub async fn post_json<T: DeserializeOwned, B: Serialize + ?Sized>(
+        &self,
+        path: &str,
+        body: &B
+    ) -> Result<T, SpaceTradersError> {
+        let response = self.send(Method::POST, path, Some(body)).await?;
+        Self::parse(response).await
+    }
+
+    /// Sends a PATCH request with a JSON body and deserializes the JSON response
+    pub async fn patch_json<T: DeserializeOwned, B: Serialize + ?Sized>(
+        &self,
+        path: &str,
+        body: &B
+    ) -> Result<T, SpaceTradersError> {
+        let response = self.send(Method::PATCH, path, Some(body)).await?;
+        Self::parse(response).await
+    }
+
+    /// Turns a response into `T`, decoding the API error envelope on non-success statuses
+    async fn parse<T: DeserializeOwned>(response: Response) -> Result<T, SpaceTradersError> {
+        let status = response.status();
+        let text = response.text().await?;
+
+        if !status.is_success() {
+            return Err(SpaceTradersError::from_response(status, &text));
+        }
+
+        Ok(serde_json::from_str(&text)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_server::{MockResponse, MockServer};
+    use serde_json::json;
+
+    #[test]
+    fn test_new_client_uses_public_api() {
+        let client = ApiClient::new("test_token");
+        assert_eq!(client.base_url(), BASE_URL);
+        assert_eq!(client.token(), Some("test_token"));
+    }
+
+    #[test]
+    fn test_anonymous_client_has_no_token() {
+        let client = ApiClient::anonymous();
+        assert_eq!(client.token(), None);
+    }
+
+    #[test]
+    fn test_url_joins_path() {
+        let client = ApiClient::new("test_token");
+        assert_eq!(client.url("/my/agent"), "https://api.spacetraders.io/v2/my/agent");
+    }
+
+    #[test]
+    fn test_with_base_url_trims_trailing_slash() {
+        let client = ApiClient::with_base_url("http://localhost:8080/v2/", None);
+        assert_eq!(client.url("/my/agent"), "http://localhost:8080/v2/my/agent");
+    }
+
+    #[tokio::test]
+    async fn test_get_sends_bearer_token() {
+        let server = MockServer::start().await;
+        server.route("GET", "/my/agent", vec![MockResponse::json(200, json!({"data": {"symbol": "AGENT-123"}}))]);
+
+        let client = ApiClient::with_base_url(server.base_url(), Some("test_token"));
+        let value: serde_json::Value = client.get("/my/agent").await.unwrap();
+
+        assert_eq!(value["data"]["symbol"], "AGENT-123");
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].headers.get("authorization").map(String::as_str), Some("Bearer test_token"));
+    }
+
+    #[tokio::test]
+    async fn test_post_json_sends_body() {
+        let server = MockServer::start().await;
+        server.route("POST", "/register", vec![MockResponse::json(201, json!({"data": {}}))]);
+
+        let client = ApiClient::with_base_url(server.base_url(), None);
+        let _: serde_json::Value = client.post_json("/register", &json!({"symbol": "AGENT-123"})).await.unwrap();
+
+        let requests = server.requests();
+        assert_eq!(requests[0].method, "POST");
+        assert!(!requests[0].headers.contains_key("authorization"));
+        assert_eq!(requests[0].body, r#"{"symbol":"AGENT-123"}"#);
+    }
+
+    #[tokio::test]
+    async fn test_clones_share_rate_limiter() {
+        let server = MockServer::start().await;
+        server.route("GET", "/my/agent", vec![MockResponse::json(200, json!({"data": {}}))]);
+
+        let client = ApiClient::with_base_url(server.base_url(), Some("test_token"));
+        let clone = client.clone();
+        let _: serde_json::Value = clone.get("/my/agent").await.unwrap();
+
+        assert!(client.rate_limit_budget().steady_available < 2.0);
+    }
+
+    fn fast_retry() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            base_delay: std::time::Duration::from_millis(1),
+            max_delay: std::time::Duration::from_millis(5),
+            ..RetryPolicy::idempotent()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_retries_rate_limited_response() {
+        let server = MockServer::start().await;
+        server.route("GET", "/my/agent", vec![
+            MockResponse::json(429, json!({"error": {"code": 429, "message": "Too Many Requests"}}))
+                .with_header("Retry-After", "0"),
+            MockResponse::json(200, json!({"data": {"symbol": "AGENT-123"}})),
+        ]);
+
+        let client = ApiClient::with_base_url(server.base_url(), Some("test_token"));
+        let value: serde_json::Value = client.get("/my/agent").await.unwrap();
+
+        assert_eq!(value["data"]["symbol"], "AGENT-123");
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_retries_exhausted_is_typed() {
+        let server = MockServer::start().await;
+        server.route("GET", "/my/agent", vec![MockResponse::json(502, json!({"error": {"message": "Bad Gateway"}}))]);
+
+        let client = ApiClient::with_base_url(server.base_url(), Some("test_token"));
+        let error = client
+            .request::<serde_json::Value, ()>(Method::GET, "/my/agent", None, &fast_retry())
+            .await
+            .unwrap_err();
+
+        let SpaceTradersError::RetriesExhausted(exhausted) = error else {
+            panic!("unexpected error: {:?}", error);
+        };
+        assert_eq!(exhausted.attempts, 3);
+        assert_eq!(exhausted.status, Some(502));
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_post_is_not_retried_on_server_error() {
+        let server = MockServer::start().await;
+        server.route("POST", "/my/ships/SHIP-1/purchase", vec![
+            MockResponse::json(502, json!({"error": {"message": "Bad Gateway"}})),
+            MockResponse::json(201, json!({"data": {}})),
+        ]);
+
+        let client = ApiClient::with_base_url(server.base_url(), Some("test_token"));
+        let result: Result<serde_json::Value, _> = client.post("/my/ships/SHIP-1/purchase").await;
+
+        assert!(result.is_err());
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_error_status_is_an_error() {
+        let server = MockServer::start().await;
+        server.route("GET", "/my/agent", vec![MockResponse::json(401, json!({"error": {"code": 401, "message": "Unauthorized"}}))]);
+
+        let client = ApiClient::with_base_url(server.base_url(), Some("bad_token"));
+        let result: Result<serde_json::Value, _> = client.get("/my/agent").await;
+
+        assert!(matches!(result, Err(SpaceTradersError::Api(error)) if error.status == 401));
+    }
+}
@@ -0,0 +1,86 @@
+//! Runtime configuration module
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::client::BASE_URL;
+
+/// Environment variable that overrides the API base URL
+pub const BASE_URL_ENV: &str = "SPACETRADERS_BASE_URL";
+
+/// File that overrides the API base URL when the environment variable is unset
+pub const BASE_URL_FILE: &str = "API_BASE_URL";
+
+/// Environment variable that overrides where the status storage snapshot is kept
+pub const STATUS_FILE_ENV: &str = "SPACETRADERS_STATUS_FILE";
+
+/// Default status storage snapshot file
+pub const STATUS_FILE: &str = "status_storage.json";
+
+/// Environment variable that overrides where the universe cache is kept
+pub const UNIVERSE_FILE_ENV: &str = "SPACETRADERS_UNIVERSE_FILE";
+
+/// Default universe cache file
+pub const UNIVERSE_FILE: &str = "universe.json";
+
+/// Reads the API base URL from the environment, then the config file, then the default
+pub fn read_base_url() -> String {
+    let from_env = env::var(BASE_URL_ENV).ok();
+    let from_file = fs::read_to_string(BASE_URL_FILE).ok();
+
+    resolve_base_url(from_env.as_deref(), from_file.as_deref())
+}
+
+/// Picks the first non-empty base URL candidate, falling back to the public API
+pub fn resolve_base_url(from_env: Option<&str>, from_file: Option<&str>) -> String {
+    [from_env, from_file]
+        .into_iter()
+        .flatten()
+        .map(|url| url.trim())
+        .find(|url| !url.is_empty())
+        .unwrap_or(BASE_URL)
+        .to_string()
+}
+
+/// Reads the status storage snapshot path from the environment, falling back to the default
+pub fn status_file_path() -> PathBuf {
+    file_path(STATUS_FILE_ENV, STATUS_FILE)
+}
+
+/// Reads the universe cache path from the environment, falling back to the default
+pub fn universe_file_path() -> PathBuf {
+    file_path(UNIVERSE_FILE_ENV, UNIVERSE_FILE)
+}
+
+/// Reads a non-empty path from an environment variable, falling back to `default`
+fn file_path(env_var: &str, default: &str) -> PathBuf {
+    env::var(env_var)
+        .ok()
+        .map(|path| path.trim().to_string())
+        .filter(|path| !path.is_empty())
+        .unwrap_or_else(|| default.to_string())
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_base_url_defaults_to_public_api() {
+        assert_eq!(resolve_base_url(None, None), BASE_URL);
+    }
+
+    #[test]
+    fn test_resolve_base_url_prefers_environment() {
+        let url = resolve_base_url(Some("http://localhost:3000"), Some("http://localhost:4000\n"));
+        assert_eq!(url, "http://localhost:3000");
+    }
+
+    #[test]
+    fn test_resolve_base_url_uses_file_when_environment_empty() {
+        let url = resolve_base_url(Some(""), Some("http://localhost:4000\n"));
+        assert_eq!(url, "http://localhost:4000");
+    }
+}
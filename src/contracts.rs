@@ -1,8 +1,11 @@
 //! Contracts information module
 
-use reqwest;
 use serde_json;
 
+use crate::client::ApiClient;
+use crate::error::SpaceTradersError;
+use crate::models::{ApiResponse, Contract};
+
 /// Structure to hold contract data
 #[derive(Debug, Clone)]
 pub struct ContractInfo {
@@ -15,283 +18,126 @@ pub struct ContractInfo {
     pub payment_on_fulfillment: i64,
 }
 
+impl From<Contract> for ContractInfo {
+    fn from(contract: Contract) -> Self {
+        let deliver = contract.terms.deliver.into_iter().next();
+        
+        ContractInfo {
+            id: contract.id,
+            faction_symbol: contract.faction_symbol,
+            contract_type: contract.contract_type.as_str().to_string(),
+            delivery_item: deliver.as_ref().map(|d| d.trade_symbol.clone()),
+            destination_symbol: deliver.as_ref().map(|d| d.destination_symbol.clone()),
+            units_required: deliver.map(|d| d.units_required as i64).unwrap_or(0),
+            payment_on_fulfillment: contract.terms.payment.on_fulfilled,
+        }
+    }
+}
+
+/// Prints the details of a contract
+fn print_contract(contract: &ContractInfo, accepted: bool) {
+    println!("ID: {}", contract.id);
+    println!("Faction: {}", contract.faction_symbol);
+    println!("Type: {}", contract.contract_type);
+    
+    if let Some(item) = &contract.delivery_item {
+        println!("Delivery Item: {}", item);
+    }
+    
+    if let Some(destination) = &contract.destination_symbol {
+        println!("Destination: {}", destination);
+    }
+    
+    println!("Units Required: {}", contract.units_required);
+    println!("Payment on Fulfillment: {} credits", contract.payment_on_fulfillment);
+    println!("Accepted: {}", accepted);
+}
+
 /// Gets contracts information from the SpaceTraders API
 pub async fn get_contracts(
-    client: &reqwest::Client,
-    token: &str
-) -> Result<Option<Vec<ContractInfo>>, Box<dyn std::error::Error>> {
+    client: &ApiClient
+) -> Result<Vec<ContractInfo>, SpaceTradersError> {
     println!("\n=== Getting Contracts ===");
     
-    let contracts_url = "https://api.spacetraders.io/v2/my/contracts";
-    
     println!("Making request to contracts endpoint...");
-    let contracts_response = client
-        .get(contracts_url)
-        .header("Authorization", format!("Bearer {}", token))
-        .send()
-        .await?;
-        
-    println!("Contracts response status: {}", contracts_response.status());
-    
-    let contracts_text = contracts_response.text().await?;
+    let contracts: Vec<Contract> = client.get_all("/my/contracts").await?;
     println!("\n=== Contract Information ===");
     
-    // Parse and pretty print contracts
+    if contracts.is_empty() {
+        println!("No active contracts found");
+    }
+    
     let mut contract_list = Vec::new();
     
-    match serde_json::from_str::<serde_json::Value>(&contracts_text) {
-        Ok(contracts_value) => {
-            if let Some(contracts_array) = contracts_value.get("data") {
-                if let Some(contracts) = contracts_array.as_array() {
-                    if contracts.is_empty() {
-                        println!("No active contracts found");
-                    } else {
-                        for (index, contract) in contracts.iter().enumerate() {
-                            println!("\n--- Contract #{} ---", index + 1);
-                            
-                            let id = contract.get("id").and_then(|s| s.as_str()).unwrap_or("").to_string();
-                            let faction_symbol = contract.get("factionSymbol").and_then(|s| s.as_str()).unwrap_or("").to_string();
-                            let contract_type = contract.get("type").and_then(|s| s.as_str()).unwrap_or("").to_string();
-                            
-                            let (delivery_item, destination_symbol, units_required, payment_on_fulfillment) = 
-                                if let Some(terms) = contract.get("terms") {
-                                    // Get delivery information
-                                    let (delivery_item, destination_symbol, units_required) = 
-                                        if let Some(deliver_array) = terms.get("deliver").and_then(|d| d.as_array()) {
-                                            if let Some(deliver) = deliver_array.first() {
-                                                (
-                                                    deliver.get("tradeSymbol").and_then(|s| s.as_str()).map(|s| s.to_string()),
-                                                    deliver.get("destinationSymbol").and_then(|s| s.as_str()).map(|s| s.to_string()),
-                                                    deliver.get("unitsRequired").and_then(|u| u.as_i64()).unwrap_or(0)
-                                                )
-                                            } else {
-                                                (None, None, 0)
-                                            }
-                                        } else {
-                                            (None, None, 0)
-                                        };
-                                    
-                                    // Get payment information
-                                    let payment_on_fulfillment = 
-                                        if let Some(payment) = terms.get("payment") {
-                                            payment.get("onFulfilled").and_then(|p| p.as_i64()).unwrap_or(0)
-                                        } else {
-                                            0
-                                        };
-                                    
-                                    (delivery_item, destination_symbol, units_required, payment_on_fulfillment)
-                                } else {
-                                    (None, None, 0, 0)
-                                };
-                            
-                            println!("ID: {}", id);
-                            println!("Faction: {}", faction_symbol);
-                            println!("Type: {}", contract_type);
-                            
-                            if let Some(item) = &delivery_item {
-                                println!("Delivery Item: {}", item);
-                            }
-                            
-                            if let Some(destination) = &destination_symbol {
-                                println!("Destination: {}", destination);
-                            }
-                            
-                            println!("Units Required: {}", units_required);
-                            println!("Payment on Fulfillment: {} credits", payment_on_fulfillment);
-                            
-                            if let Some(accepted) = contract.get("accepted") {
-                                println!("Accepted: {}", accepted);
-                            }
-                            
-                            // Store the contract info
-                            contract_list.push(ContractInfo {
-                                id,
-                                faction_symbol,
-                                contract_type,
-                                delivery_item,
-                                destination_symbol,
-                                units_required,
-                                payment_on_fulfillment
-                            });
-                        }
-                    }
-                }
-            }
-        }
-        Err(e) => {
-            println!("Error parsing contracts data: {:?}", e);
-        }
+    for (index, contract) in contracts.into_iter().enumerate() {
+        println!("\n--- Contract #{} ---", index + 1);
+        
+        let accepted = contract.accepted;
+        let contract_info = ContractInfo::from(contract);
+        print_contract(&contract_info, accepted);
+        
+        contract_list.push(contract_info);
     }
     
-    Ok(Some(contract_list))
+    Ok(contract_list)
 }
 
 /// Gets a specific contract by ID from the SpaceTraders API
 pub async fn get_contract_by_id(
-    client: &reqwest::Client,
-    token: &str,
+    client: &ApiClient,
     contract_id: &str
-) -> Result<Option<ContractInfo>, Box<dyn std::error::Error>> {
+) -> Result<ContractInfo, SpaceTradersError> {
     println!("\n=== Getting Contract by ID ===");
     
-    let contract_url = format!("https://api.spacetraders.io/v2/my/contracts/{}", contract_id);
-    
-    println!("Making request to contract endpoint: {}", contract_url);
-    let contract_response = client
-        .get(&contract_url)
-        .header("Authorization", format!("Bearer {}", token))
-        .send()
-        .await?;
-        
-    println!("Contract response status: {}", contract_response.status());
+    let contract_path = format!("/my/contracts/{}", contract_id);
     
-    let contract_text = contract_response.text().await?;
+    println!("Making request to contract endpoint: {}", client.url(&contract_path));
+    let response: ApiResponse<Contract> = client.get(&contract_path).await?;
     println!("\n=== Contract Details ===");
     
-    // Parse and pretty print contract
-    match serde_json::from_str::<serde_json::Value>(&contract_text) {
-        Ok(contract_value) => {
-            if let Some(contract_data) = contract_value.get("data") {
-                // Extract contract details
-                let id = contract_data.get("id").and_then(|s| s.as_str()).unwrap_or("").to_string();
-                let faction_symbol = contract_data.get("factionSymbol").and_then(|s| s.as_str()).unwrap_or("").to_string();
-                let contract_type = contract_data.get("type").and_then(|s| s.as_str()).unwrap_or("").to_string();
-                
-                let (delivery_item, destination_symbol, units_required, payment_on_fulfillment) = 
-                    if let Some(terms) = contract_data.get("terms") {
-                        // Get delivery information
-                        let (delivery_item, destination_symbol, units_required) = 
-                            if let Some(deliver_array) = terms.get("deliver").and_then(|d| d.as_array()) {
-                                if let Some(deliver) = deliver_array.first() {
-                                    (
-                                        deliver.get("tradeSymbol").and_then(|s| s.as_str()).map(|s| s.to_string()),
-                                        deliver.get("destinationSymbol").and_then(|s| s.as_str()).map(|s| s.to_string()),
-                                        deliver.get("unitsRequired").and_then(|u| u.as_i64()).unwrap_or(0)
-                                    )
-                                } else {
-                                    (None, None, 0)
-                                }
-                            } else {
-                                (None, None, 0)
-                            };
-                        
-                        // Get payment information
-                        let payment_on_fulfillment = 
-                            if let Some(payment) = terms.get("payment") {
-                                payment.get("onFulfilled").and_then(|p| p.as_i64()).unwrap_or(0)
-                            } else {
-                                0
-                            };
-                        
-                        (delivery_item, destination_symbol, units_required, payment_on_fulfillment)
-                    } else {
-                        (None, None, 0, 0)
-                    };
-                
-                println!("ID: {}", id);
-                println!("Faction: {}", faction_symbol);
-                println!("Type: {}", contract_type);
-                
-                if let Some(item) = &delivery_item {
-                    println!("Delivery Item: {}", item);
-                }
-                
-                if let Some(destination) = &destination_symbol {
-                    println!("Destination: {}", destination);
-                }
-                
-                println!("Units Required: {}", units_required);
-                println!("Payment on Fulfillment: {} credits", payment_on_fulfillment);
-                
-                if let Some(accepted) = contract_data.get("accepted") {
-                    println!("Accepted: {}", accepted);
-                }
-                
-                // Return the contract info
-                Ok(Some(ContractInfo {
-                    id,
-                    faction_symbol,
-                    contract_type,
-                    delivery_item,
-                    destination_symbol,
-                    units_required,
-                    payment_on_fulfillment
-                }))
-            } else {
-                println!("No contract data found in response");
-                Ok(None)
-            }
-        }
-        Err(e) => {
-            println!("Error parsing contract data: {:?}", e);
-            Ok(None)
-        }
-    }
+    let accepted = response.data.accepted;
+    let contract_info = ContractInfo::from(response.data);
+    print_contract(&contract_info, accepted);
+    
+    Ok(contract_info)
 }
 
 /// Accepts a contract by ID from the SpaceTraders API
 pub async fn accept_contract(
-    client: &reqwest::Client,
-    token: &str,
+    client: &ApiClient,
     contract_id: &str
-) -> Result<bool, Box<dyn std::error::Error>> {
+) -> Result<(), SpaceTradersError> {
     println!("\n=== Accepting Contract ===");
     
-    let accept_url = format!("https://api.spacetraders.io/v2/my/contracts/{}/accept", contract_id);
+    let accept_path = format!("/my/contracts/{}/accept", contract_id);
     
-    println!("Making request to accept contract endpoint: {}", accept_url);
-    let accept_response = client
-        .post(&accept_url)
-        .header("Authorization", format!("Bearer {}", token))
-        .send()
-        .await?;
-        
-    println!("Accept contract response status: {}", accept_response.status());
+    println!("Making request to accept contract endpoint: {}", client.url(&accept_path));
+    let _: serde_json::Value = client.post(&accept_path).await?;
     
-    // Check if the request was successful
-    if accept_response.status().is_success() {
-        println!("Contract accepted successfully!");
-        Ok(true)
-    } else {
-        let error_text = accept_response.text().await?;
-        println!("Failed to accept contract: {}", error_text);
-        Ok(false)
-    }
+    println!("Contract accepted successfully!");
+    Ok(())
 }
 
 /// Fulfill a delivery contract by ID from the SpaceTraders API
 pub async fn fulfill_delivery(
-    client: &reqwest::Client,
-    token: &str,
+    client: &ApiClient,
     contract_id: &str
-) -> Result<bool, Box<dyn std::error::Error>> {
+) -> Result<(), SpaceTradersError> {
     println!("\n=== Fulfilling Delivery Contract ===");
     
-    let fulfill_url = format!("https://api.spacetraders.io/v2/my/contracts/{}/fulfill", contract_id);
+    let fulfill_path = format!("/my/contracts/{}/fulfill", contract_id);
     
-    println!("Making request to fulfill delivery endpoint: {}", fulfill_url);
-    let fulfill_response = client
-        .post(&fulfill_url)
-        .header("Authorization", format!("Bearer {}", token))
-        .send()
-        .await?;
-        
-    println!("Fulfill delivery response status: {}", fulfill_response.status());
+    println!("Making request to fulfill delivery endpoint: {}", client.url(&fulfill_path));
+    let _: serde_json::Value = client.post(&fulfill_path).await?;
     
-    // Check if the request was successful
-    if fulfill_response.status().is_success() {
-        println!("Delivery fulfilled successfully!");
-        Ok(true)
-    } else {
-        let error_text = fulfill_response.text().await?;
-        println!("Failed to fulfill delivery: {}", error_text);
-        Ok(false)
-    }
+    println!("Delivery fulfilled successfully!");
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::mock_server::{MockResponse, MockServer};
     use serde_json::json;
     
     #[tokio::test]
@@ -318,32 +164,105 @@ mod tests {
     
     #[tokio::test]
     async fn test_parse_contracts_empty() {
-        // Test parsing empty contracts response
-        let _json_response = json!({
-            "data": []
-        });
+        let server = MockServer::start().await;
+        server.route("GET", "/my/contracts", vec![MockResponse::json(200, json!({
+            "data": [],
+            "meta": {"total": 0, "page": 1, "limit": 20}
+        }))]);
         
-        // This would normally be tested with a mock HTTP client
-        // For now, we're just ensuring the structure compiles and is valid
-        assert!(true); // Placeholder test - actual HTTP mocking would be needed for real testing
+        let client = ApiClient::with_base_url(server.base_url(), Some("test_token"));
+        let contracts = get_contracts(&client).await.unwrap();
+        
+        assert!(contracts.is_empty());
     }
-    
+
+    #[tokio::test]
+    async fn test_get_contracts_reads_every_page() {
+        let contract = |id: usize| json!({
+            "id": format!("contract-{}", id),
+            "factionSymbol": "COSMIC",
+            "type": "PROCUREMENT",
+            "terms": {
+                "deadline": "2026-10-23T00:00:00.000Z",
+                "deliver": [],
+                "payment": {"onAccepted": 1000, "onFulfilled": 5000}
+            },
+            "accepted": false,
+            "fulfilled": false
+        });
+        let first: Vec<_> = (0..20).map(contract).collect();
+        let second: Vec<_> = (20..25).map(contract).collect();
+
+        let server = MockServer::start().await;
+        server.route("GET", "/my/contracts?page=1&limit=20", vec![MockResponse::json(200, json!({
+            "data": first,
+            "meta": {"total": 25, "page": 1, "limit": 20}
+        }))]);
+        server.route("GET", "/my/contracts?page=2&limit=20", vec![MockResponse::json(200, json!({
+            "data": second,
+            "meta": {"total": 25, "page": 2, "limit": 20}
+        }))]);
+
+        let client = ApiClient::with_base_url(server.base_url(), Some("test_token"));
+        let contracts = get_contracts(&client).await.unwrap();
+
+        assert_eq!(contracts.len(), 25);
+        assert_eq!(contracts[24].id, "contract-24");
+    }
+
     #[tokio::test]
     async fn test_get_contract_by_id_function() {
-        // Test that the get_contract_by_id function exists and compiles
-        // Note: This is a basic test that just ensures the function signature works
-        assert!(true); // Placeholder - actual testing would require mocking HTTP calls
+        let server = MockServer::start().await;
+        server.route("GET", "/my/contracts/contract-123", vec![MockResponse::json(200, json!({
+            "data": {
+                "id": "contract-123",
+                "factionSymbol": "COSMIC",
+                "type": "PROCUREMENT",
+                "terms": {
+                    "deadline": "2026-10-23T00:00:00.000Z",
+                    "deliver": [{
+                        "tradeSymbol": "IRON_ORE",
+                        "destinationSymbol": "X1-ABCD-1234",
+                        "unitsRequired": 50,
+                        "unitsFulfilled": 0
+                    }],
+                    "payment": {"onAccepted": 1000, "onFulfilled": 5000}
+                },
+                "accepted": false,
+                "fulfilled": false
+            }
+        }))]);
+        
+        let client = ApiClient::with_base_url(server.base_url(), Some("test_token"));
+        let contract = get_contract_by_id(&client, "contract-123").await.unwrap();
+        
+        assert_eq!(contract.id, "contract-123");
+        assert_eq!(contract.faction_symbol, "COSMIC");
+        assert_eq!(contract.delivery_item.as_deref(), Some("IRON_ORE"));
+        assert_eq!(contract.units_required, 50);
+        assert_eq!(contract.payment_on_fulfillment, 5000);
     }
     
     #[tokio::test]
     async fn test_accept_contract_function() {
-        // Test that the accept_contract function exists and compiles
-        assert!(true); // Placeholder - actual testing would require mocking HTTP calls
+        let server = MockServer::start().await;
+        server.route("POST", "/my/contracts/contract-123/accept", vec![MockResponse::json(200, json!({"data": {}}))]);
+        
+        let client = ApiClient::with_base_url(server.base_url(), Some("test_token"));
+        
+        assert!(accept_contract(&client, "contract-123").await.is_ok());
     }
     
     #[tokio::test]
     async fn test_fulfill_delivery_function() {
-        // Test that the fulfill_delivery function exists and compiles
-        assert!(true); // Placeholder - actual testing would require mocking HTTP calls
+        let server = MockServer::start().await;
+        server.route("POST", "/my/contracts/contract-123/fulfill", vec![MockResponse::json(400, json!({
+            "error": {"code": 4504, "message": "Contract terms not met"}
+        }))]);
+        
+        let client = ApiClient::with_base_url(server.base_url(), Some("test_token"));
+        
+        let error = fulfill_delivery(&client, "contract-123").await.unwrap_err();
+        assert_eq!(error.api_error().map(|e| e.code), Some(4504));
     }
 }
@@ -2,6 +2,11 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::models::ShipNavFlightMode;
+
+/// Fixed part of every flight's duration, in seconds
+pub const BASE_FLIGHT_SECONDS: f64 = 15.0;
+
 /// Structure to represent a 2D coordinate point
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Point {
@@ -144,10 +149,63 @@ impl Asteroid {
     }
 }
 
+/// Distance the game charges for: rounded to whole units, at least one
+fn billed_distance(distance: f64) -> f64 {
+    distance.round().max(1.0)
+}
+
+/// How much slower than a unit of speed a flight mode travels
+pub fn flight_mode_multiplier(mode: ShipNavFlightMode) -> f64 {
+    match mode {
+        ShipNavFlightMode::Burn => 12.5,
+        ShipNavFlightMode::Cruise => 25.0,
+        ShipNavFlightMode::Stealth => 30.0,
+        ShipNavFlightMode::Drift => 250.0,
+    }
+}
+
+/// Fuel a flight over `distance` burns in a flight mode
+///
+/// Drifting always costs exactly one fuel; burning costs double, at least two.
+pub fn fuel_cost(distance: f64, mode: ShipNavFlightMode) -> i32 {
+    let distance = billed_distance(distance) as i32;
+    match mode {
+        ShipNavFlightMode::Drift => 1,
+        ShipNavFlightMode::Burn => 2 * distance,
+        ShipNavFlightMode::Cruise | ShipNavFlightMode::Stealth => distance,
+    }
+}
+
+/// Seconds a flight over `distance` takes with an engine speed and flight mode
+pub fn travel_seconds(distance: f64, speed: i32, mode: ShipNavFlightMode) -> u64 {
+    let flight = billed_distance(distance) * flight_mode_multiplier(mode) / speed.max(1) as f64;
+    (flight + BASE_FLIGHT_SECONDS).round() as u64
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_fuel_cost_per_flight_mode() {
+        assert_eq!(fuel_cost(50.4, ShipNavFlightMode::Cruise), 50);
+        assert_eq!(fuel_cost(50.5, ShipNavFlightMode::Stealth), 51);
+        assert_eq!(fuel_cost(50.4, ShipNavFlightMode::Burn), 100);
+        assert_eq!(fuel_cost(50.4, ShipNavFlightMode::Drift), 1);
+        // Even a hop between orbitals is billed as one unit of distance
+        assert_eq!(fuel_cost(0.0, ShipNavFlightMode::Cruise), 1);
+        assert_eq!(fuel_cost(0.2, ShipNavFlightMode::Burn), 2);
+    }
+
+    #[test]
+    fn test_travel_seconds_per_flight_mode() {
+        assert_eq!(travel_seconds(50.0, 10, ShipNavFlightMode::Cruise), 140);
+        assert_eq!(travel_seconds(50.0, 10, ShipNavFlightMode::Burn), 78);
+        assert_eq!(travel_seconds(50.0, 10, ShipNavFlightMode::Stealth), 165);
+        assert_eq!(travel_seconds(50.0, 10, ShipNavFlightMode::Drift), 1265);
+        assert_eq!(travel_seconds(0.0, 30, ShipNavFlightMode::Cruise), 16);
+    }
+
     #[test]
     fn test_point_distance() {
         let p1 = Point::new(0, 0);
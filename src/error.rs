@@ -0,0 +1,287 @@
+//! Error types for the SpaceTraders client
+//!
+//! The API reports failures as `{"error": {"code", "message", "data"}}`. The
+//! codes the agent needs to react to get their own variant; everything else is
+//! kept as a generic `Api` error with the decoded body.
+
+use std::fmt;
+
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+use crate::retry::RetriesExhausted;
+
+/// Error codes documented by the SpaceTraders API
+pub mod codes {
+    pub const COOLDOWN_CONFLICT: u32 = 4000;
+    pub const NAVIGATE_IN_TRANSIT: u32 = 4200;
+    pub const SHIP_IN_TRANSIT: u32 = 4214;
+    pub const SHIP_CARGO_EXCEEDS_LIMIT: u32 = 4217;
+    pub const SHIP_SURVEY_VERIFICATION: u32 = 4220;
+    pub const SHIP_SURVEY_EXPIRATION: u32 = 4221;
+    pub const SHIP_SURVEY_EXHAUSTED: u32 = 4224;
+    pub const SHIP_CARGO_FULL: u32 = 4228;
+    pub const CONTRACT_DEADLINE: u32 = 4503;
+    pub const MARKET_TRADE_INSUFFICIENT_CREDITS: u32 = 4600;
+    pub const MARKET_TRADE_UNIT_LIMIT: u32 = 4604;
+}
+
+/// Decoded `error` object of an API error response
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ApiError {
+    #[serde(default)]
+    pub status: u16,
+    #[serde(default)]
+    pub code: u32,
+    #[serde(default)]
+    pub message: String,
+    #[serde(default)]
+    pub data: Option<serde_json::Value>,
+}
+
+/// Envelope wrapping the `error` object
+#[derive(Debug, Deserialize)]
+struct ErrorEnvelope {
+    error: ApiError,
+}
+
+/// Everything that can go wrong when talking to the SpaceTraders API
+#[derive(Debug)]
+pub enum SpaceTradersError {
+    /// The request could not be sent or the response could not be read
+    Http(reqwest::Error),
+    /// The response body did not match the expected shape
+    Parse(serde_json::Error),
+    /// A local file (token, config) could not be read
+    Io(std::io::Error),
+    /// The request was still rejected after every allowed retry
+    RetriesExhausted(RetriesExhausted),
+    /// The response was valid JSON but is missing data we rely on
+    UnexpectedResponse(String),
+    /// The server was reset and the agent token is no longer valid
+    TokenReset(ApiError),
+    /// The agent cannot afford a purchase
+    InsufficientFunds(ApiError),
+    /// The ship is still travelling to its destination
+    ShipInTransit(ApiError),
+    /// The ship's reactor or mounts are cooling down
+    CooldownActive { remaining_seconds: u64, error: ApiError },
+    /// The ship has no room left in its cargo hold
+    CargoFull(ApiError),
+    /// The contract deadline has passed
+    ContractExpired(ApiError),
+    /// The survey has been mined out and can no longer be used
+    SurveyExhausted(ApiError),
+    /// The survey's signature failed verification
+    SurveyInvalid(ApiError),
+    /// The survey has expired
+    SurveyExpired(ApiError),
+    /// The order exceeds the good's trade volume at the market
+    TradeLimitExceeded(ApiError),
+    /// The request was rate limited
+    RateLimited(ApiError),
+    /// Any other error reported by the API
+    Api(ApiError),
+}
+
+impl SpaceTradersError {
+    /// Builds the error for a non-success response from its status and body
+    pub fn from_response(status: StatusCode, body: &str) -> Self {
+        let mut error = serde_json::from_str::<ErrorEnvelope>(body)
+            .map(|envelope| envelope.error)
+            .unwrap_or_else(|_| ApiError {
+                status: 0,
+                code: status.as_u16() as u32,
+                message: body.to_string(),
+                data: None,
+            });
+        error.status = status.as_u16();
+        Self::from_api_error(error)
+    }
+
+    /// Maps a decoded API error onto the variant callers branch on
+    pub fn from_api_error(error: ApiError) -> Self {
+        match error.code {
+            codes::COOLDOWN_CONFLICT => {
+                let remaining_seconds = error
+                    .data
+                    .as_ref()
+                    .and_then(|d| d.get("cooldown"))
+                    .and_then(|c| c.get("remainingSeconds"))
+                    .and_then(|s| s.as_u64())
+                    .unwrap_or(0);
+                Self::CooldownActive { remaining_seconds, error }
+            }
+            codes::NAVIGATE_IN_TRANSIT | codes::SHIP_IN_TRANSIT => Self::ShipInTransit(error),
+            codes::SHIP_CARGO_EXCEEDS_LIMIT | codes::SHIP_CARGO_FULL => Self::CargoFull(error),
+            codes::SHIP_SURVEY_EXHAUSTED => Self::SurveyExhausted(error),
+            codes::SHIP_SURVEY_VERIFICATION => Self::SurveyInvalid(error),
+            codes::SHIP_SURVEY_EXPIRATION => Self::SurveyExpired(error),
+            codes::CONTRACT_DEADLINE => Self::ContractExpired(error),
+            codes::MARKET_TRADE_INSUFFICIENT_CREDITS => Self::InsufficientFunds(error),
+            codes::MARKET_TRADE_UNIT_LIMIT => Self::TradeLimitExceeded(error),
+            429 => Self::RateLimited(error),
+            401 if error.message.contains("reset") => Self::TokenReset(error),
+            _ => Self::Api(error),
+        }
+    }
+
+    /// Returns the decoded API error, if the server reported one
+    pub fn api_error(&self) -> Option<&ApiError> {
+        match self {
+            Self::TokenReset(error)
+            | Self::InsufficientFunds(error)
+            | Self::ShipInTransit(error)
+            | Self::CooldownActive { error, .. }
+            | Self::CargoFull(error)
+            | Self::ContractExpired(error)
+            | Self::SurveyExhausted(error)
+            | Self::SurveyInvalid(error)
+            | Self::SurveyExpired(error)
+            | Self::TradeLimitExceeded(error)
+            | Self::RateLimited(error)
+            | Self::Api(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for SpaceTradersError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Http(e) => write!(f, "HTTP error: {}", e),
+            Self::Parse(e) => write!(f, "Could not parse response: {}", e),
+            Self::Io(e) => write!(f, "I/O error: {}", e),
+            Self::RetriesExhausted(e) => write!(f, "{}", e),
+            Self::UnexpectedResponse(message) => write!(f, "Unexpected response: {}", message),
+            Self::CooldownActive { remaining_seconds, error } => write!(
+                f,
+                "Cooldown active for {} more seconds (code {}): {}",
+                remaining_seconds, error.code, error.message
+            ),
+            other => {
+                let error = other.api_error().expect("remaining variants carry an API error");
+                write!(f, "API error {} (HTTP {}): {}", error.code, error.status, error.message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SpaceTradersError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Http(e) => Some(e),
+            Self::Parse(e) => Some(e),
+            Self::Io(e) => Some(e),
+            Self::RetriesExhausted(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for SpaceTradersError {
+    fn from(error: reqwest::Error) -> Self {
+        Self::Http(error)
+    }
+}
+
+impl From<serde_json::Error> for SpaceTradersError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::Parse(error)
+    }
+}
+
+impl From<std::io::Error> for SpaceTradersError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<RetriesExhausted> for SpaceTradersError {
+    fn from(error: RetriesExhausted) -> Self {
+        Self::RetriesExhausted(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cooldown_error_carries_remaining_seconds() {
+        let body = r#"{"error":{"code":4000,"message":"Ship action is still on cooldown","data":{"cooldown":{"shipSymbol":"SHIP-1","totalSeconds":70,"remainingSeconds":42}}}}"#;
+
+        match SpaceTradersError::from_response(StatusCode::CONFLICT, body) {
+            SpaceTradersError::CooldownActive { remaining_seconds, error } => {
+                assert_eq!(remaining_seconds, 42);
+                assert_eq!(error.status, 409);
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_known_codes_map_to_variants() {
+        let decode = |code: u32| {
+            let body = format!(r#"{{"error":{{"code":{},"message":"test"}}}}"#, code);
+            SpaceTradersError::from_response(StatusCode::BAD_REQUEST, &body)
+        };
+
+        assert!(matches!(decode(4214), SpaceTradersError::ShipInTransit(_)));
+        assert!(matches!(decode(4228), SpaceTradersError::CargoFull(_)));
+        assert!(matches!(decode(4224), SpaceTradersError::SurveyExhausted(_)));
+        assert!(matches!(decode(4502), SpaceTradersError::Api(_)));
+        assert!(matches!(decode(4600), SpaceTradersError::InsufficientFunds(_)));
+        assert!(matches!(decode(4999), SpaceTradersError::Api(_)));
+    }
+
+    #[test]
+    fn test_navigate_in_transit_is_decoded() {
+        let body = r#"{"error":{"message":"Ship is currently in-transit from X1-DF55-20250Z to X1-DF55-B7 and arrives in 42 seconds.","code":4200,"data":{"departureSymbol":"X1-DF55-20250Z","destinationSymbol":"X1-DF55-B7","arrival":"2026-10-16T12:00:42.000Z","departureTime":"2026-10-16T11:58:00.000Z","secondsToArrival":42}}}"#;
+        let error = SpaceTradersError::from_response(StatusCode::BAD_REQUEST, body);
+        assert!(matches!(error, SpaceTradersError::ShipInTransit(ref e) if e.code == codes::NAVIGATE_IN_TRANSIT));
+    }
+
+    #[test]
+    fn test_survey_verification_failure_is_decoded() {
+        let body = r#"{"error":{"message":"Ship survey failed. Target signature X1-DF55-A1-5A1B2C is no longer in a valid state.","code":4220,"data":{"shipSymbol":"SHIP-1","surveySignature":"X1-DF55-A1-5A1B2C"}}}"#;
+        let error = SpaceTradersError::from_response(StatusCode::BAD_REQUEST, body);
+        assert!(matches!(error, SpaceTradersError::SurveyInvalid(ref e) if e.code == codes::SHIP_SURVEY_VERIFICATION));
+    }
+
+    #[test]
+    fn test_survey_expiration_is_decoded() {
+        let body = r#"{"error":{"message":"Ship survey failed. Target signature X1-DF55-A1-5A1B2C has expired.","code":4221,"data":{"surveySignature":"X1-DF55-A1-5A1B2C","expiration":"2026-10-16T11:00:00.000Z"}}}"#;
+        let error = SpaceTradersError::from_response(StatusCode::BAD_REQUEST, body);
+        assert!(matches!(error, SpaceTradersError::SurveyExpired(ref e) if e.code == codes::SHIP_SURVEY_EXPIRATION));
+    }
+
+    #[test]
+    fn test_contract_deadline_is_decoded() {
+        let body = r#"{"error":{"message":"Contract cm1a2b3c4d5e6f7g8h has exceeded its deadline and can no longer be fulfilled.","code":4503,"data":{"contractId":"cm1a2b3c4d5e6f7g8h","deadline":"2026-10-15T12:00:00.000Z"}}}"#;
+        let error = SpaceTradersError::from_response(StatusCode::BAD_REQUEST, body);
+        assert!(matches!(error, SpaceTradersError::ContractExpired(ref e) if e.code == codes::CONTRACT_DEADLINE));
+    }
+
+    #[test]
+    fn test_trade_unit_limit_is_decoded() {
+        let body = r#"{"error":{"message":"Market transaction failed. Trade units 40 exceeds the limit of 20 for IRON_ORE at X1-DF55-B7.","code":4604,"data":{"waypointSymbol":"X1-DF55-B7","tradeSymbol":"IRON_ORE","units":40,"tradeVolume":20}}}"#;
+        let error = SpaceTradersError::from_response(StatusCode::BAD_REQUEST, body);
+        assert!(matches!(error, SpaceTradersError::TradeLimitExceeded(ref e) if e.code == codes::MARKET_TRADE_UNIT_LIMIT));
+    }
+
+    #[test]
+    fn test_token_reset_is_detected() {
+        let body = r#"{"error":{"code":401,"message":"Failed to parse token. Token reset_date does not match the server."}}"#;
+        let error = SpaceTradersError::from_response(StatusCode::UNAUTHORIZED, body);
+        assert!(matches!(error, SpaceTradersError::TokenReset(_)));
+    }
+
+    #[test]
+    fn test_non_json_body_is_kept_as_message() {
+        let error = SpaceTradersError::from_response(StatusCode::BAD_GATEWAY, "<html>Bad Gateway</html>");
+        let api_error = error.api_error().unwrap();
+        assert_eq!(api_error.status, 502);
+        assert_eq!(api_error.message, "<html>Bad Gateway</html>");
+    }
+}
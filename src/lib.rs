@@ -3,7 +3,28 @@
 pub mod agent;
 pub mod agent_management;
 pub mod asteroid;
+pub mod client;
+pub mod config;
 pub mod contracts;
 pub mod distance;
+pub mod error;
+pub mod markets;
+pub mod mining;
+#[cfg(test)]
+mod mock_server;
+pub mod models;
+pub mod navigation;
+pub mod pagination;
+pub mod pathfinding;
+pub mod price_history;
+pub mod rate_limit;
+pub mod retry;
+pub mod shared_storage;
+pub mod ships;
 pub mod status_storage;
+pub mod systems;
 pub mod token;
+pub mod trade_routes;
+pub mod trader;
+pub mod trading;
+pub mod universe;
@@ -1,13 +1,24 @@
 //! SpaceTraders Agent - Autonomous game player
 
-use reqwest;
+use std::time::Duration;
+
 use spacetraders_oc_qwen3::agent;
-use spacetraders_oc_qwen3::agent_management;
+use spacetraders_oc_qwen3::client::ApiClient;
+use spacetraders_oc_qwen3::config;
+use spacetraders_oc_qwen3::error::SpaceTradersError;
+use spacetraders_oc_qwen3::models::{Ship, ShipNav, ShipNavStatus};
+use spacetraders_oc_qwen3::navigation;
+use spacetraders_oc_qwen3::ships;
+use spacetraders_oc_qwen3::shared_storage::SharedStatusStorage;
+use spacetraders_oc_qwen3::status_storage::{CachePolicy, StatusStorage};
+use spacetraders_oc_qwen3::systems;
 use spacetraders_oc_qwen3::contracts;
 use spacetraders_oc_qwen3::token;
+use spacetraders_oc_qwen3::universe::{self, UniverseCache};
 use spacetraders_oc_qwen3::asteroid;
 use spacetraders_oc_qwen3::distance;
-use spacetraders_oc_qwen3::status_storage;
+use spacetraders_oc_qwen3::markets::{self, SellPrices};
+use spacetraders_oc_qwen3::mining;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -18,170 +29,232 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     println!("Using agent token (length: {})", clean_token.len());
     
-    let client = reqwest::Client::new();
+    let base_url = config::read_base_url();
+    println!("Using API base URL: {}", base_url);
+    
+    let client = ApiClient::with_base_url(&base_url, Some(&clean_token));
     
     // Get agent info first
-    let agent_data = agent_management::get_agent_info(&client, &clean_token).await?;
+    let agent_data = match agent::get_agent_info(&client).await {
+        Ok(agent) => agent,
+        Err(SpaceTradersError::TokenReset(error)) => {
+            println!("The server has been reset and the agent token is no longer valid: {}", error.message);
+            println!("Register a new agent and update the AGENT_TOKEN file.");
+            return Ok(());
+        }
+        Err(e) => return Err(e.into()),
+    };
     
     // Try to get contracts
-    let _contract_data = contracts::get_contracts(&client, &clean_token).await?;
-    
-    // Get current system from agent data and scan for asteroids
-    let target_system = if let Some(current_system) = &agent_data.system_symbol {
-        println!("Scanning current system: {}", current_system);
-        current_system.clone()
-    } else {
-        println!("Could not determine current system. Scanning for valid systems...");
-        // Try to find a valid system to work with
-        match get_valid_system(&client, &clean_token).await {
-            Ok(system) => {
-                println!("Using system: {}", system);
-                system
-            },
-            Err(_) => {
-                // If we can't find a valid system, use a known good one
-                println!("Using default system: X1-TT88");
-                "X1-TT88".to_string()
+    let _contract_data = contracts::get_contracts(&client).await?;
+    
+    // Resume from the last saved statuses and surveys, then record where every ship is
+    let status_file = config::status_file_path();
+    let status_storage = SharedStatusStorage::new(StatusStorage::load(&status_file, CachePolicy::default())?);
+    println!("Loaded {} ship statuses from {}", status_storage.read().await.len(), status_file.display());
+    let _expiry_task = status_storage.spawn_expiry_task(Duration::from_secs(60));
+    let _save_task = status_storage.spawn_save_task(&status_file, Duration::from_secs(30));
+    let fleet = ships::refresh_fleet_status(&client, &status_storage).await;
+    status_storage.save(&status_file).await?;
+    if let Ok(fleet) = &fleet {
+        println!("\n=== Fleet Status ===");
+        for status in status_storage.read().await.get_all_valid_statuses() {
+            println!("{}: {:?} at {} (fuel {})", status.ship_symbol, status.status_type, status.location, status.fuel);
+        }
+        println!("Tracking {} ships", fleet.len());
+    }
+    
+    // Work in the system our ships are in, falling back to headquarters
+    let lead_ship = fleet.map(|ships| ships.into_iter().next());
+    let target_system = match &lead_ship {
+        Ok(Some(ship)) => {
+            println!("Scanning current system: {}", ship.nav.system_symbol);
+            ship.nav.system_symbol.clone()
+        }
+        Ok(None) => {
+            println!("Agent has no ships. Scanning headquarters system...");
+            agent_data.headquarters_system()
+        }
+        Err(e) => {
+            println!("Could not look up ship locations: {}", e);
+            println!("Scanning for valid systems...");
+            // Try to find a valid system to work with
+            match get_valid_system(&client).await {
+                Ok(system) => {
+                    println!("Using system: {}", system);
+                    system
+                },
+                Err(_) => {
+                    // If we can't find a valid system, use a known good one
+                    println!("Using default system: X1-TT88");
+                    "X1-TT88".to_string()
+                }
             }
         }
     };
     
+    // Systems and waypoints are charted once per server reset
+    let universe_file = config::universe_file_path();
+    let mut universe_cache = UniverseCache::load(&universe_file)?;
+    match universe::sync_reset_date(&client, &mut universe_cache).await {
+        Ok(true) => println!("Universe reset on {}, charting from scratch", universe_cache.reset_date()),
+        Ok(false) => println!("Loaded {} charted systems from {}", universe_cache.len(), universe_file.display()),
+        Err(e) => println!("Could not check the server reset date: {}", e),
+    }
+    
     // For now, we'll look for common metal traits in asteroids
     let required_materials = vec!["COMMON_METALS", "RARE_METALS"];
     
     // First scan for asteroids with materials
-    asteroid::scan_for_asteroids_with_materials(&client, &clean_token, &target_system, &required_materials).await?;
+    let closest_asteroid = asteroid::scan_for_asteroids_with_materials(
+        &client,
+        &mut universe_cache,
+        &target_system,
+        &required_materials
+    ).await?;
+    universe_cache.save(&universe_file)?;
+    
+    // Send our first ship to the closest matching asteroid
+    let mut lead_nav = match &lead_ship {
+        Ok(Some(ship)) => Some(ship.nav.clone()),
+        _ => None,
+    };
+    if let (Ok(Some(ship)), Some(asteroid)) = (&lead_ship, &closest_asteroid) {
+        lead_nav = Some(move_ship_to(&client, &status_storage, ship, &asteroid.symbol).await?);
+        status_storage.save(&status_file).await?;
+    }
     
-    // Now demonstrate survey functionality
+    // Survey the asteroid our first ship is already at
     println!("\n=== Demonstrating Survey Functionality ===");
     
-    // Get a list of asteroids in the system to survey
-    let asteroid_waypoints = get_asteroid_waypoints(&client, &clean_token, &target_system).await?;
-    
-    if !asteroid_waypoints.is_empty() {
-        // Survey the first asteroid in the list
-        let first_asteroid = &asteroid_waypoints[0];
-        println!("Surveying asteroid: {}", first_asteroid);
-        
-        // Perform the survey
-        let survey_info = asteroid::survey_asteroid(&client, &clean_token, first_asteroid).await?;
-        
-        // Add survey to status storage
-        // Note: In a real implementation, we would properly integrate with the status storage system
-        println!("Survey data stored in cache: {:?}", survey_info);
-    } else {
-        println!("No asteroids found to survey");
+    match (&lead_ship, &mut lead_nav) {
+        (Ok(Some(ship)), Some(nav)) if nav.status == ShipNavStatus::InTransit => {
+            println!("{} is in transit to {}, skipping the survey", ship.symbol, nav.route.destination.symbol);
+        }
+        (Ok(Some(ship)), Some(nav)) if nav.route.destination.waypoint_type.contains("ASTEROID") => {
+            // Surveying only works from orbit
+            if nav.status == ShipNavStatus::Docked {
+                *nav = navigation::orbit_ship(&client, &status_storage, &ship.symbol).await?.nav;
+            }
+            println!("Surveying asteroid: {}", nav.waypoint_symbol);
+            match asteroid::survey_asteroid(&client, &status_storage, &ship.symbol).await {
+                Ok(result) => {
+                    status_storage.save(&status_file).await?;
+                    println!(
+                        "{} surveys stored for {}",
+                        result.surveys.len(),
+                        nav.waypoint_symbol
+                    );
+                }
+                Err(SpaceTradersError::ShipInTransit(_)) => {
+                    println!("{} is still in transit, skipping the survey", ship.symbol);
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+        _ => println!("No ship at an asteroid to survey with"),
+    }
+
+    // Mine with the stored surveys, ranked by what the nearest market pays
+    if let (Ok(Some(ship)), Some(nav)) = (&lead_ship, &lead_nav)
+        && nav.status == ShipNavStatus::InOrbit
+        && nav.route.destination.waypoint_type.contains("ASTEROID")
+    {
+        println!("\n=== Mining {} ===", nav.waypoint_symbol);
+        let prices = match markets::nearest_sell_prices(&client, &status_storage, &mut universe_cache, &nav.waypoint_symbol).await? {
+            Some(observed) => {
+                println!(
+                    "Ranking surveys by {} prices at {}, observed {}s ago",
+                    observed.prices.len(),
+                    observed.market,
+                    observed.age()
+                );
+                observed.prices
+            }
+            None => {
+                println!("No marketplace near {}, ranking surveys by size only", nav.waypoint_symbol);
+                SellPrices::new()
+            }
+        };
+        universe_cache.save(&universe_file)?;
+
+        let yields = mining::mine_until_full(&client, &status_storage, &ship.symbol, &nav.waypoint_symbol, &prices).await?;
+        status_storage.save(&status_file).await?;
+        println!("{} extracted {} units", ship.symbol, yields.iter().map(|y| y.units).sum::<i32>());
     }
 
     // Demonstrate distance tracking functionality
-    demonstrate_distance_tracking(&client, &clean_token).await?;
+    demonstrate_distance_tracking(&client).await?;
+
+    status_storage.save(&status_file).await?;
 
     Ok(())
 }
 
-/// Get a list of asteroid waypoints in a system
-async fn get_asteroid_waypoints(
-    client: &reqwest::Client,
-    token: &str,
-    system_symbol: &str
-) -> Result<Vec<String>, Box<dyn std::error::Error>> {
-    println!("\n=== Getting asteroid waypoints ===");
-    
-    // Get the system information to find waypoints
-    let system_url = format!("https://api.spacetraders.io/v2/systems/{}", system_symbol);
-    
-    let response = client
-        .get(&system_url)
-        .header("Authorization", format!("Bearer {}", token))
-        .send()
-        .await?;
-        
-    println!("System response status: {}", response.status());
-    
-    let system_text = response.text().await?;
-    
-    // Parse and look for asteroid waypoints
-    match serde_json::from_str::<serde_json::Value>(&system_text) {
-        Ok(system_value) => {
-            if let Some(waypoints) = system_value.get("data").and_then(|d| d.get("waypoints")) {
-                if let Some(waypoint_array) = waypoints.as_array() {
-                    println!("Found {} waypoints in system", waypoint_array.len());
-                    
-                    let mut asteroid_waypoints = Vec::new();
-                    
-                    for waypoint in waypoint_array {
-                        if let Some(waypoint_type) = waypoint.get("type") {
-                            // Look for asteroid-related waypoints
-                            if let Some(type_str) = waypoint_type.as_str() {
-                                if type_str.contains("ASTEROID") || type_str == "ASTEROID_FIELD" {
-                                    let waypoint_symbol = waypoint.get("symbol").and_then(|s| s.as_str()).unwrap_or("Unknown");
-                                    println!("Found asteroid waypoint: {}", waypoint_symbol);
-                                    asteroid_waypoints.push(waypoint_symbol.to_string());
-                                }
-                            }
-                        }
-                    }
-                    
-                    Ok(asteroid_waypoints)
-                } else {
-                    Ok(Vec::new())
-                }
-            } else {
-                Ok(Vec::new())
-            }
-        },
-        Err(e) => {
-            println!("Error parsing system data: {:?}", e);
-            Ok(Vec::new())
+/// Move a ship to a waypoint in its system, leaving it docked or orbiting as needed
+///
+/// Returns the ship's navigation state after the move, which is in transit
+/// if a flight was started.
+async fn move_ship_to(
+    client: &ApiClient,
+    status_storage: &SharedStatusStorage,
+    ship: &Ship,
+    waypoint_symbol: &str
+) -> Result<ShipNav, SpaceTradersError> {
+    println!("\n=== Moving {} to {} ===", ship.symbol, waypoint_symbol);
+    
+    if ship.nav.waypoint_symbol == waypoint_symbol {
+        println!("{} is already at {}", ship.symbol, waypoint_symbol);
+        return Ok(ship.nav.clone());
+    }
+    
+    match ship.nav.status {
+        ShipNavStatus::InTransit => {
+            println!("{} is still in transit, arriving at {}", ship.symbol, ship.nav.route.arrival);
+            return Ok(ship.nav.clone());
+        }
+        ShipNavStatus::Docked => {
+            navigation::orbit_ship(client, status_storage, &ship.symbol).await?;
         }
+        ShipNavStatus::InOrbit => {}
     }
+    
+    let update = navigation::navigate_ship(client, status_storage, &ship.symbol, waypoint_symbol).await?;
+    
+    Ok(update.nav)
 }
 
 /// Get a valid system to work with when we can't determine the current one
-async fn get_valid_system(client: &reqwest::Client, token: &str) -> Result<String, Box<dyn std::error::Error>> {
-    // Try to get a list of systems from the API
-    let systems_url = "https://api.spacetraders.io/v2/systems";
-    
-    let response = client
-        .get(systems_url)
-        .header("Authorization", format!("Bearer {}", token))
-        .send()
-        .await?;
-        
-    if response.status().is_success() {
-        let systems_text = response.text().await?;
-        // Parse the system list and return a valid one
-        if let Ok(systems_value) = serde_json::from_str::<serde_json::Value>(&systems_text) {
-            if let Some(systems_array) = systems_value.get("data").and_then(|d| d.as_array()) {
-                // Return the first system in the list as a fallback
-                if let Some(first_system) = systems_array.first() {
-                    if let Some(symbol) = first_system.get("symbol").and_then(|s| s.as_str()) {
-                        return Ok(symbol.to_string());
-                    }
-                }
-            }
-        }
+async fn get_valid_system(client: &ApiClient) -> Result<String, SpaceTradersError> {
+    // Return the first system the API lists as a fallback
+    if let Some(system) = systems::first_system(client).await? {
+        return Ok(system.symbol);
     }
     
     // If we can't get a valid system list, return an error
-    Err("Could not determine a valid system to work with".into())
+    Err(SpaceTradersError::UnexpectedResponse("Could not determine a valid system to work with".to_string()))
 }
 
 /// Demonstrate distance tracking functionality
 async fn demonstrate_distance_tracking(
-    client: &reqwest::Client,
-    token: &str
-) -> Result<(), Box<dyn std::error::Error>> {
+    client: &ApiClient
+) -> Result<(), SpaceTradersError> {
     println!("\n=== Demonstrating Distance Tracking ===");
     
     // Get current agent position
-    let (current_x, current_y) = asteroid::get_agent_position(client, token).await?;
+    let (current_x, current_y) = match asteroid::get_agent_position(client).await {
+        Ok(position) => position,
+        Err(e) => {
+            println!("Could not determine agent position: {}", e);
+            return Ok(());
+        }
+    };
     let agent_location = distance::Location::new("AGENT".to_string(), current_x, current_y);
     
     println!("Agent location: ({}, {})", agent_location.point.x, agent_location.point.y);
     
     // Create some sample locations to demonstrate distance calculations
-    let locations = vec![
+    let locations = [
         distance::Location::new("LOCATION_1".to_string(), 10, 20),
         distance::Location::new("LOCATION_2".to_string(), -5, 15),
         distance::Location::new("LOCATION_3".to_string(), 30, -10),
@@ -0,0 +1,310 @@
+//! Market lookups and prices
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::client::ApiClient;
+use crate::distance::Point;
+use crate::error::SpaceTradersError;
+use crate::models::{ApiResponse, Market, system_symbol_of};
+use crate::shared_storage::SharedStatusStorage;
+use crate::status_storage::{CacheCategory, Freshness, unix_now};
+use crate::universe::{UniverseCache, system_waypoints};
+
+/// Credits a market pays per unit, keyed by trade symbol
+pub type SellPrices = HashMap<String, i64>;
+
+/// A market as it was seen at a point in time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketObservation {
+    pub market: Market,
+    /// Unix time the market was fetched
+    pub observed_at: u64,
+}
+
+impl MarketObservation {
+    /// Records a market fetched just now
+    pub fn now(market: Market) -> Self {
+        Self { market, observed_at: unix_now() }
+    }
+
+    /// Whether trade goods and prices were visible, i.e. a ship was present
+    pub fn has_prices(&self) -> bool {
+        self.market.trade_goods.is_some()
+    }
+}
+
+/// Sell prices of a market and when they were observed
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObservedPrices {
+    pub market: String,
+    pub prices: SellPrices,
+    /// Unix time of the oldest of the prices
+    pub observed_at: u64,
+}
+
+impl ObservedPrices {
+    /// Seconds since the prices were observed
+    pub fn age(&self) -> u64 {
+        unix_now().saturating_sub(self.observed_at)
+    }
+}
+
+/// Gets a market; trade goods and prices are only included with a ship present
+pub async fn get_market(
+    client: &ApiClient,
+    waypoint_symbol: &str
+) -> Result<Market, SpaceTradersError> {
+    let path = format!("/systems/{}/waypoints/{}/market", system_symbol_of(waypoint_symbol), waypoint_symbol);
+    let response: ApiResponse<Market> = client.get(&path).await?;
+    Ok(response.data)
+}
+
+/// Fetches a market, caching it with the time it was observed and recording its prices
+///
+/// A fetch without prices does not replace a cached observation that has them.
+pub async fn observe_market(
+    client: &ApiClient,
+    storage: &SharedStatusStorage,
+    waypoint_symbol: &str
+) -> Result<MarketObservation, SpaceTradersError> {
+    let observation = MarketObservation::now(get_market(client, waypoint_symbol).await?);
+    if observation.has_prices() || cached_with_prices(storage, waypoint_symbol).await.is_none() {
+        storage.cache_put(CacheCategory::Markets, waypoint_symbol, &observation).await?;
+    }
+    storage.record_prices(&observation).await;
+    Ok(observation)
+}
+
+/// Gets a market from the storage, fetching it if it is missing or stale
+///
+/// Under stale-while-revalidate a stale observation is returned at once and
+/// refreshed in the background. A refetch without prices keeps the cached
+/// observation that has them; check `observed_at` for its age.
+pub async fn cached_market(
+    client: &ApiClient,
+    storage: &SharedStatusStorage,
+    waypoint_symbol: &str
+) -> Result<MarketObservation, SpaceTradersError> {
+    let client = client.clone();
+    let history = storage.clone();
+    let symbol = waypoint_symbol.to_string();
+    storage
+        .cached(CacheCategory::Markets, waypoint_symbol, move || async move {
+            let observation = MarketObservation::now(get_market(&client, &symbol).await?);
+            if !observation.has_prices()
+                && let Some(priced) = cached_with_prices(&history, &symbol).await
+            {
+                return Ok(priced);
+            }
+            history.record_prices(&observation).await;
+            Ok(observation)
+        })
+        .await
+}
+
+/// The cached observation of a market, fresh or stale, if it has prices
+async fn cached_with_prices(storage: &SharedStatusStorage, waypoint_symbol: &str) -> Option<MarketObservation> {
+    match storage.cache_get::<MarketObservation>(CacheCategory::Markets, waypoint_symbol).await {
+        Freshness::Fresh(observation) | Freshness::Stale(observation) if observation.has_prices() => Some(observation),
+        _ => None,
+    }
+}
+
+/// Sell prices of every good the market lists prices for
+pub fn sell_prices(market: &Market) -> SellPrices {
+    market
+        .trade_goods
+        .iter()
+        .flatten()
+        .map(|good| (good.symbol.clone(), good.sell_price))
+        .collect()
+}
+
+/// Finds the marketplace closest to a waypoint in the same system
+///
+/// The system is charted into the universe cache the first time it is seen.
+pub async fn nearest_marketplace(
+    client: &ApiClient,
+    universe: &mut UniverseCache,
+    waypoint_symbol: &str
+) -> Result<Option<String>, SpaceTradersError> {
+    let waypoints = system_waypoints(client, universe, &system_symbol_of(waypoint_symbol)).await?;
+
+    let Some(origin) = waypoints.iter().find(|w| w.symbol == waypoint_symbol) else {
+        return Ok(None);
+    };
+    let origin = Point::new(origin.x, origin.y);
+
+    let nearest = waypoints
+        .iter()
+        .filter(|w| w.has_trait("MARKETPLACE"))
+        .min_by(|a, b| {
+            let dist_a = origin.distance_to(&Point::new(a.x, a.y));
+            let dist_b = origin.distance_to(&Point::new(b.x, b.y));
+            dist_a.partial_cmp(&dist_b).unwrap_or(std::cmp::Ordering::Equal)
+        })
+        .map(|w| w.symbol.clone());
+
+    Ok(nearest)
+}
+
+/// Sell prices at the marketplace closest to a waypoint, or `None` if there is none
+///
+/// The market is read through the cache, so a fresh observation is reused.
+/// Prices are only visible with a ship present. Otherwise the market's latest
+/// prices in the price history are used and `observed_at` says how old they
+/// are; a market never seen with a ship yields no prices.
+pub async fn nearest_sell_prices(
+    client: &ApiClient,
+    storage: &SharedStatusStorage,
+    universe: &mut UniverseCache,
+    waypoint_symbol: &str
+) -> Result<Option<ObservedPrices>, SpaceTradersError> {
+    let Some(market_symbol) = nearest_marketplace(client, universe, waypoint_symbol).await? else {
+        return Ok(None);
+    };
+
+    let observation = cached_market(client, storage, &market_symbol).await?;
+    if observation.has_prices() {
+        return Ok(Some(ObservedPrices {
+            prices: sell_prices(&observation.market),
+            observed_at: observation.observed_at,
+            market: market_symbol,
+        }));
+    }
+
+    let storage = storage.read().await;
+    let latest = storage.price_history().latest_at(&market_symbol);
+    Ok(Some(ObservedPrices {
+        prices: latest.iter().map(|(symbol, point)| (symbol.to_string(), point.sell_price)).collect(),
+        observed_at: latest.iter().map(|(_, point)| point.observed_at).min().unwrap_or(observation.observed_at),
+        market: market_symbol,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_server::{MockServer, market, market_response, trade_good};
+    use crate::models::{MarketTradeGood, SupplyLevel, TradeGood};
+    use serde_json::json;
+
+    fn waypoint(symbol: &str, x: i32, y: i32, traits: &[&str]) -> serde_json::Value {
+        let traits: Vec<_> = traits.iter().map(|t| json!({"symbol": t, "name": t, "description": ""})).collect();
+        json!({"symbol": symbol, "type": "PLANET", "systemSymbol": "X1-DF55", "x": x, "y": y, "traits": traits})
+    }
+
+    /// Market at `X1-DF55-A1` importing iron ore
+    fn iron_market(sell_price: i64) -> Market {
+        let iron = MarketTradeGood { supply: SupplyLevel::Limited, ..trade_good("IRON_ORE", 70, sell_price, 60) };
+        let import = TradeGood {
+            symbol: "IRON_ORE".to_string(),
+            name: "Iron Ore".to_string(),
+            description: String::new(),
+        };
+        Market { imports: vec![import], ..market("X1-DF55-A1", vec![iron]) }
+    }
+
+    #[tokio::test]
+    async fn test_cached_market_reuses_observation() {
+        let server = MockServer::start().await;
+        server.route("GET", "/systems/X1-DF55/waypoints/X1-DF55-A1/market", vec![
+            market_response(&iron_market(65)),
+            market_response(&iron_market(80)),
+        ]);
+
+        let client = ApiClient::with_base_url(server.base_url(), Some("test_token"));
+        let storage = SharedStatusStorage::default();
+        let before = unix_now();
+        let observed = observe_market(&client, &storage, "X1-DF55-A1").await.unwrap();
+        let cached = cached_market(&client, &storage, "X1-DF55-A1").await.unwrap();
+
+        assert!(observed.has_prices());
+        assert!(observed.observed_at >= before);
+        assert_eq!(cached.observed_at, observed.observed_at);
+        assert_eq!(cached.market.imports[0].symbol, "IRON_ORE");
+        assert_eq!(sell_prices(&cached.market).get("IRON_ORE"), Some(&65));
+        assert_eq!(server.requests().len(), 1);
+
+        let storage = storage.read().await;
+        let point = storage.price_history().latest("X1-DF55-A1", "IRON_ORE").unwrap();
+        assert_eq!(point.observed_at, observed.observed_at);
+        assert_eq!(point.supply, SupplyLevel::Limited);
+    }
+
+    #[tokio::test]
+    async fn test_observation_without_ship_keeps_cached_prices() {
+        let server = MockServer::start().await;
+        server.route("GET", "/systems/X1-DF55/waypoints/X1-DF55-A1/market", vec![
+            market_response(&iron_market(65)),
+            market_response(&Market { trade_goods: None, ..iron_market(65) }),
+        ]);
+
+        let client = ApiClient::with_base_url(server.base_url(), Some("test_token"));
+        let storage = SharedStatusStorage::default();
+        let priced = observe_market(&client, &storage, "X1-DF55-A1").await.unwrap();
+        let unpriced = observe_market(&client, &storage, "X1-DF55-A1").await.unwrap();
+        let cached = cached_market(&client, &storage, "X1-DF55-A1").await.unwrap();
+
+        assert!(!unpriced.has_prices());
+        assert_eq!(cached.observed_at, priced.observed_at);
+        assert_eq!(sell_prices(&cached.market).get("IRON_ORE"), Some(&65));
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_nearest_sell_prices_uses_closest_marketplace() {
+        let server = MockServer::start().await;
+        server.route("GET", "/systems/X1-DF55/waypoints/X1-DF55-NEAR/market", vec![
+            market_response(&market("X1-DF55-NEAR", vec![trade_good("IRON_ORE", 60, 55, 60)])),
+        ]);
+
+        let client = ApiClient::with_base_url(server.base_url(), Some("test_token"));
+        let mut universe = UniverseCache::new("2026-10-11");
+        let waypoints = vec![
+            waypoint("X1-DF55-A1", 0, 0, &[]),
+            waypoint("X1-DF55-FAR", 100, 100, &["MARKETPLACE"]),
+            waypoint("X1-DF55-NEAR", 5, -5, &["MARKETPLACE"]),
+        ];
+        universe.insert_waypoints("X1-DF55", serde_json::from_value(json!(waypoints)).unwrap());
+
+        let storage = SharedStatusStorage::default();
+        let prices = nearest_sell_prices(&client, &storage, &mut universe, "X1-DF55-A1").await.unwrap().unwrap();
+
+        assert_eq!(prices.market, "X1-DF55-NEAR");
+        assert_eq!(prices.prices.get("IRON_ORE"), Some(&55));
+        assert!(prices.age() <= 1);
+        // Waypoints come from the universe cache, only the market is fetched
+        assert_eq!(server.requests().len(), 1);
+
+        // A fresh observation is reused instead of fetching the market again
+        let again = nearest_sell_prices(&client, &storage, &mut universe, "X1-DF55-A1").await.unwrap().unwrap();
+        assert_eq!(again.prices, prices.prices);
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_nearest_sell_prices_fall_back_to_history_without_ship() {
+        let server = MockServer::start().await;
+        // Without a ship present the market lists its goods but no prices
+        server.route("GET", "/systems/X1-DF55/waypoints/X1-DF55-A1/market", vec![
+            market_response(&Market { trade_goods: None, ..iron_market(70) }),
+        ]);
+
+        let client = ApiClient::with_base_url(server.base_url(), Some("test_token"));
+        let mut universe = UniverseCache::new("2026-10-11");
+        let waypoints = vec![waypoint("X1-DF55-A1", 0, 0, &["MARKETPLACE"])];
+        universe.insert_waypoints("X1-DF55", serde_json::from_value(json!(waypoints)).unwrap());
+
+        let storage = SharedStatusStorage::default();
+        storage.record_prices(&MarketObservation { market: iron_market(62), observed_at: 1_000 }).await;
+
+        let prices = nearest_sell_prices(&client, &storage, &mut universe, "X1-DF55-A1").await.unwrap().unwrap();
+
+        assert_eq!(prices.prices.get("IRON_ORE"), Some(&62));
+        assert_eq!(prices.observed_at, 1_000);
+        assert!(prices.age() > 0);
+    }
+}
@@ -0,0 +1,260 @@
+//! Resource extraction at asteroids
+
+use std::time::Duration;
+
+use crate::client::ApiClient;
+use crate::error::SpaceTradersError;
+use crate::markets::SellPrices;
+use crate::models::{ApiResponse, Cooldown, ExtractionResult, ExtractionYield, Survey, SurveySize};
+use crate::shared_storage::SharedStatusStorage;
+use crate::status_storage::StatusStorage;
+
+/// Shortest wait before retrying an extraction rejected for an active cooldown
+const MIN_COOLDOWN_RETRY: Duration = Duration::from_secs(1);
+
+/// Extracts resources at the ship's current waypoint
+pub async fn extract_resources(
+    client: &ApiClient,
+    ship_symbol: &str
+) -> Result<ExtractionResult, SpaceTradersError> {
+    let response: ApiResponse<ExtractionResult> = client
+        .post(&format!("/my/ships/{}/extract", ship_symbol))
+        .await?;
+    Ok(response.data)
+}
+
+/// Extracts resources targeting the deposits of a survey
+pub async fn extract_with_survey(
+    client: &ApiClient,
+    ship_symbol: &str,
+    survey: &Survey
+) -> Result<ExtractionResult, SpaceTradersError> {
+    let response: ApiResponse<ExtractionResult> = client
+        .post_json(&format!("/my/ships/{}/extract/survey", ship_symbol), survey)
+        .await?;
+    Ok(response.data)
+}
+
+/// How much a survey's size multiplies its value; larger deposits last more extractions
+fn size_weight(size: SurveySize) -> f64 {
+    match size {
+        SurveySize::Small => 1.0,
+        SurveySize::Moderate => 1.5,
+        SurveySize::Large => 2.0,
+    }
+}
+
+/// Expected credits per extracted unit when mining with a survey, weighted by size
+///
+/// Every deposit entry is an equally likely yield, so a good listed twice is
+/// twice as likely. Goods the market does not price are worth nothing.
+pub fn survey_value(survey: &Survey, prices: &SellPrices) -> f64 {
+    if survey.deposits.is_empty() {
+        return 0.0;
+    }
+
+    let total: i64 = survey
+        .deposits
+        .iter()
+        .map(|deposit| prices.get(&deposit.symbol).copied().unwrap_or(0))
+        .sum();
+    total as f64 / survey.deposits.len() as f64 * size_weight(survey.size)
+}
+
+/// Orders surveys from most to least valuable
+pub fn rank_surveys(surveys: Vec<Survey>, prices: &SellPrices) -> Vec<(Survey, f64)> {
+    let mut ranked: Vec<(Survey, f64)> = surveys
+        .into_iter()
+        .map(|survey| {
+            let value = survey_value(&survey, prices);
+            (survey, value)
+        })
+        .collect();
+    ranked.sort_by(|(a, value_a), (b, value_b)| {
+        value_b
+            .partial_cmp(value_a)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| (b.size, b.deposits.len()).cmp(&(a.size, a.deposits.len())))
+    });
+    ranked
+}
+
+/// Picks the most valuable non-expired survey of a waypoint
+pub fn best_survey(storage: &StatusStorage, waypoint_symbol: &str, prices: &SellPrices) -> Option<Survey> {
+    rank_surveys(storage.get_surveys(waypoint_symbol), prices).into_iter().next().map(|(survey, _)| survey)
+}
+
+/// Sleeps until the cooldown has expired
+pub async fn wait_for_cooldown(cooldown: &Cooldown) {
+    let remaining = cooldown.remaining();
+    if !remaining.is_zero() {
+        println!("{} cooling down for {:.1}s", cooldown.ship_symbol, remaining.as_secs_f64());
+        tokio::time::sleep(remaining).await;
+    }
+}
+
+/// Extracts at the ship's waypoint until its cargo hold is full
+///
+/// Uses the most valuable stored survey for the waypoint, priced with
+/// `prices`, and drops surveys the server reports as exhausted or expired.
+pub async fn mine_until_full(
+    client: &ApiClient,
+    storage: &SharedStatusStorage,
+    ship_symbol: &str,
+    waypoint_symbol: &str,
+    prices: &SellPrices
+) -> Result<Vec<ExtractionYield>, SpaceTradersError> {
+    let mut yields = Vec::new();
+
+    loop {
+        let survey = best_survey(&*storage.read().await, waypoint_symbol, prices);
+        let result = match &survey {
+            Some(survey) => extract_with_survey(client, ship_symbol, survey).await,
+            None => extract_resources(client, ship_symbol).await,
+        };
+
+        let result = match result {
+            Ok(result) => result,
+            Err(SpaceTradersError::CooldownActive { remaining_seconds, .. }) => {
+                // The server rounds down, so zero may still mean a fraction of a second
+                tokio::time::sleep(Duration::from_secs(remaining_seconds).max(MIN_COOLDOWN_RETRY)).await;
+                continue;
+            }
+            Err(
+                SpaceTradersError::SurveyExhausted(_)
+                | SpaceTradersError::SurveyInvalid(_)
+                | SpaceTradersError::SurveyExpired(_)
+            ) if survey.is_some() => {
+                let signature = survey.map(|s| s.signature).unwrap_or_default();
+                println!("Survey {} can no longer be used, dropping it", signature);
+                storage.remove_survey(&signature).await;
+                continue;
+            }
+            Err(SpaceTradersError::CargoFull(_)) => break,
+            Err(e) => return Err(e),
+        };
+
+        let extracted = result.extraction.extraction_yield;
+        println!(
+            "{} extracted {} {} ({}/{} cargo)",
+            ship_symbol, extracted.units, extracted.symbol, result.cargo.units, result.cargo.capacity
+        );
+        yields.push(extracted);
+
+        if result.cargo.units >= result.cargo.capacity {
+            break;
+        }
+        wait_for_cooldown(&result.cooldown).await;
+    }
+
+    Ok(yields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_server::{MockResponse, MockServer};
+    use crate::models::SurveyDeposit;
+    use serde_json::json;
+
+    fn stored_survey(waypoint: &str, size: SurveySize, deposits: &[&str]) -> Survey {
+        Survey {
+            signature: format!("{}-{:?}-SIG", waypoint, size).to_uppercase(),
+            symbol: waypoint.to_string(),
+            deposits: deposits.iter().map(|d| SurveyDeposit { symbol: d.to_string() }).collect(),
+            expiration: "2099-01-01T00:00:00.000Z".to_string(),
+            size,
+        }
+    }
+
+    fn extraction(units: i32, cargo_units: i32) -> MockResponse {
+        MockResponse::json(201, json!({
+            "data": {
+                "cooldown": {"shipSymbol": "SHIP-1", "totalSeconds": 0, "remainingSeconds": 0},
+                "extraction": {"shipSymbol": "SHIP-1", "yield": {"symbol": "IRON_ORE", "units": units}},
+                "cargo": {"capacity": 15, "units": cargo_units, "inventory": []}
+            }
+        }))
+    }
+
+    #[test]
+    fn test_best_survey_only_considers_waypoint() {
+        let mut storage = StatusStorage::new();
+        storage.update_survey(stored_survey("X1-DF55-A1", SurveySize::Small, &["IRON_ORE"]));
+        storage.update_survey(stored_survey("X1-DF55-A1", SurveySize::Moderate, &["IRON_ORE"]));
+        storage.update_survey(stored_survey("X1-DF55-A2", SurveySize::Large, &["IRON_ORE"]));
+
+        let survey = best_survey(&storage, "X1-DF55-A1", &SellPrices::new()).unwrap();
+        assert_eq!(survey.symbol, "X1-DF55-A1");
+        assert_eq!(survey.size, SurveySize::Moderate);
+        assert!(best_survey(&storage, "X1-DF55-A3", &SellPrices::new()).is_none());
+    }
+
+    #[test]
+    fn test_surveys_ranked_by_expected_credits() {
+        let prices = SellPrices::from([
+            ("IRON_ORE".to_string(), 40),
+            ("COPPER_ORE".to_string(), 20),
+            ("GOLD_ORE".to_string(), 200),
+        ]);
+
+        let iron = stored_survey("X1-DF55-A1", SurveySize::Large, &["IRON_ORE", "IRON_ORE", "COPPER_ORE"]);
+        let gold = stored_survey("X1-DF55-A1", SurveySize::Small, &["GOLD_ORE", "QUARTZ_SAND"]);
+
+        assert_eq!(survey_value(&iron, &prices), 200.0 / 3.0);
+        assert_eq!(survey_value(&gold, &prices), 100.0);
+
+        let ranked = rank_surveys(vec![iron, gold], &prices);
+        assert_eq!(ranked[0].0.deposits[0].symbol, "GOLD_ORE");
+    }
+
+    #[tokio::test]
+    async fn test_mining_drops_exhausted_survey() {
+        let server = MockServer::start().await;
+        server.route("POST", "/my/ships/SHIP-1/extract/survey", vec![MockResponse::json(409, json!({
+            "error": {"code": 4224, "message": "Survey has been exhausted"}
+        }))]);
+        server.route("POST", "/my/ships/SHIP-1/extract", vec![extraction(7, 7), extraction(8, 15)]);
+
+        let client = ApiClient::with_base_url(server.base_url(), Some("test_token"));
+        let storage = SharedStatusStorage::default();
+        storage.update_survey(stored_survey("X1-DF55-A1", SurveySize::Large, &["IRON_ORE"])).await;
+
+        let yields = mine_until_full(&client, &storage, "SHIP-1", "X1-DF55-A1", &SellPrices::new()).await.unwrap();
+
+        assert_eq!(yields.iter().map(|y| y.units).sum::<i32>(), 15);
+        assert!(storage.read().await.get_surveys("X1-DF55-A1").is_empty());
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 3);
+        let submitted: serde_json::Value = serde_json::from_str(&requests[0].body).unwrap();
+        assert_eq!(submitted["signature"], "X1-DF55-A1-LARGE-SIG");
+        assert_eq!(submitted["size"], "LARGE");
+        assert_eq!(submitted["expiration"], "2099-01-01T00:00:00.000Z");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_mining_waits_out_cooldown_reported_as_zero() {
+        let server = MockServer::start().await;
+        server.route("POST", "/my/ships/SHIP-1/extract", vec![
+            MockResponse::json(409, json!({
+                "error": {
+                    "code": 4000,
+                    "message": "Ship action is still on cooldown",
+                    "data": {"cooldown": {"shipSymbol": "SHIP-1", "totalSeconds": 70, "remainingSeconds": 0}}
+                }
+            })),
+            extraction(15, 15),
+        ]);
+
+        let client = ApiClient::with_base_url(server.base_url(), Some("test_token"));
+        let storage = SharedStatusStorage::default();
+
+        let start = tokio::time::Instant::now();
+        let yields = mine_until_full(&client, &storage, "SHIP-1", "X1-DF55-A1", &SellPrices::new()).await.unwrap();
+
+        assert_eq!(yields.len(), 1);
+        assert_eq!(server.requests().len(), 2);
+        assert!(start.elapsed() >= MIN_COOLDOWN_RETRY);
+    }
+}
@@ -0,0 +1,330 @@
+//! Minimal HTTP stand-in for the SpaceTraders API, used by unit tests
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+use crate::models::{Market, MarketTradeGood, MarketTradeGoodType, SupplyLevel};
+
+/// Canned response returned for a matching route
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    pub status: u16,
+    pub body: String,
+    pub headers: Vec<(String, String)>,
+}
+
+impl MockResponse {
+    /// Creates a response with the given status and JSON body
+    pub fn json(status: u16, body: serde_json::Value) -> Self {
+        Self {
+            status,
+            body: body.to_string(),
+            headers: Vec::new(),
+        }
+    }
+
+    /// Adds a header to the response
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+}
+
+/// Request received by the mock server
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    pub headers: HashMap<String, String>,
+    pub body: String,
+}
+
+type Routes = HashMap<(String, String), Vec<MockResponse>>;
+
+/// Local HTTP server answering each route with a queue of canned responses
+///
+/// Routes are matched on method and path; a route registered with a query
+/// string only matches that exact query. The last response of a queue is
+/// repeated once the earlier ones have been served.
+pub struct MockServer {
+    base_url: String,
+    routes: Arc<Mutex<Routes>>,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+}
+
+impl MockServer {
+    /// Starts a server on a random local port
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind mock server");
+        let base_url = format!("http://{}", listener.local_addr().expect("mock server address"));
+        let routes: Arc<Mutex<Routes>> = Arc::new(Mutex::new(HashMap::new()));
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let server_routes = routes.clone();
+        let server_requests = requests.clone();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let routes = server_routes.clone();
+                let requests = server_requests.clone();
+                tokio::spawn(async move {
+                    let Some(request) = read_request(&mut stream).await else {
+                        return;
+                    };
+                    let response = next_response(&routes, &request);
+                    requests.lock().unwrap().push(request);
+
+                    let mut raw = format!(
+                        "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+                        response.status,
+                        response.body.len()
+                    );
+                    for (name, value) in &response.headers {
+                        raw.push_str(&format!("{}: {}\r\n", name, value));
+                    }
+                    raw.push_str("\r\n");
+                    raw.push_str(&response.body);
+                    let _ = stream.write_all(raw.as_bytes()).await;
+                    let _ = stream.shutdown().await;
+                });
+            }
+        });
+
+        Self {
+            base_url,
+            routes,
+            requests,
+        }
+    }
+
+    /// Base URL to hand to an `ApiClient`
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Registers the responses served, in order, for a method and path
+    pub fn route(&self, method: &str, path: &str, responses: Vec<MockResponse>) {
+        self.routes
+            .lock()
+            .unwrap()
+            .insert((method.to_string(), path.to_string()), responses);
+    }
+
+    /// Returns every request received so far
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+/// Reads one HTTP/1.1 request from the stream
+async fn read_request(stream: &mut tokio::net::TcpStream) -> Option<RecordedRequest> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
+
+    let header_end = loop {
+        let read = stream.read(&mut chunk).await.ok()?;
+        if read == 0 {
+            return None;
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+        if let Some(position) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+            break position + 4;
+        }
+    };
+
+    let head = String::from_utf8_lossy(&buffer[..header_end]).to_string();
+    let mut lines = head.lines();
+    let mut request_line = lines.next()?.split_whitespace();
+    let method = request_line.next()?.to_string();
+    let path = request_line.next()?.to_string();
+
+    let headers: HashMap<String, String> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+        .collect();
+
+    let content_length = headers
+        .get("content-length")
+        .and_then(|l| l.parse::<usize>().ok())
+        .unwrap_or(0);
+    while buffer.len() < header_end + content_length {
+        let read = stream.read(&mut chunk).await.ok()?;
+        if read == 0 {
+            break;
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    }
+    let body = String::from_utf8_lossy(&buffer[header_end..]).to_string();
+
+    Some(RecordedRequest {
+        method,
+        path,
+        headers,
+        body,
+    })
+}
+
+/// Pops the next response for a request, falling back to a 404 envelope
+fn next_response(routes: &Mutex<Routes>, request: &RecordedRequest) -> MockResponse {
+    let mut routes = routes.lock().unwrap();
+    let bare_path = request.path.split('?').next().unwrap_or("").to_string();
+
+    let key = [request.path.clone(), bare_path]
+        .into_iter()
+        .map(|path| (request.method.clone(), path))
+        .find(|key| routes.contains_key(key));
+
+    match key.and_then(|key| routes.get_mut(&key)) {
+        Some(queue) if queue.len() > 1 => queue.remove(0),
+        Some(queue) if !queue.is_empty() => queue[0].clone(),
+        _ => MockResponse::json(
+            404,
+            serde_json::json!({"error": {"code": 404, "message": "Not found"}}),
+        ),
+    }
+}
+
+/// Ship as returned by `/my/ships`, docked at `X1-DF55-20250Z` with 10 units of iron ore
+pub fn ship_json(symbol: &str) -> serde_json::Value {
+    let waypoint = serde_json::json!({
+        "symbol": "X1-DF55-20250Z",
+        "type": "PLANET",
+        "systemSymbol": "X1-DF55",
+        "x": 10,
+        "y": 20
+    });
+
+    serde_json::json!({
+        "symbol": symbol,
+        "registration": {"name": symbol, "factionSymbol": "COSMIC", "role": "EXCAVATOR"},
+        "nav": {
+            "systemSymbol": "X1-DF55",
+            "waypointSymbol": "X1-DF55-20250Z",
+            "route": {
+                "destination": waypoint,
+                "origin": waypoint,
+                "departureTime": "2026-10-16T12:00:00.000Z",
+                "arrival": "2026-10-16T12:00:00.000Z"
+            },
+            "status": "DOCKED",
+            "flightMode": "CRUISE"
+        },
+        "crew": {"current": 0, "required": 0, "capacity": 0, "rotation": "STRICT", "morale": 100, "wages": 0},
+        "frame": {
+            "symbol": "FRAME_DRONE",
+            "name": "Drone",
+            "moduleSlots": 2,
+            "mountingPoints": 1,
+            "fuelCapacity": 400,
+            "requirements": {"power": 1, "crew": 0}
+        },
+        "reactor": {"symbol": "REACTOR_CHEMICAL_I", "name": "Chemical Reactor I", "powerOutput": 15, "requirements": {"crew": 3}},
+        "engine": {"symbol": "ENGINE_IMPULSE_DRIVE_I", "name": "Impulse Drive I", "speed": 10, "requirements": {"power": 1}},
+        "cooldown": {"shipSymbol": symbol, "totalSeconds": 0, "remainingSeconds": 0},
+        "modules": [{"symbol": "MODULE_CARGO_HOLD_I", "capacity": 15, "name": "Cargo Hold", "requirements": {"slots": 1}}],
+        "mounts": [{"symbol": "MOUNT_MINING_LASER_I", "name": "Mining Laser I", "strength": 10, "requirements": {"power": 1}}],
+        "cargo": {
+            "capacity": 15,
+            "units": 10,
+            "inventory": [{"symbol": "IRON_ORE", "name": "Iron Ore", "description": "", "units": 10}]
+        },
+        "fuel": {"current": 350, "capacity": 400}
+    })
+}
+
+/// Trade good exchanged at a market with moderate supply
+pub fn trade_good(symbol: &str, purchase_price: i64, sell_price: i64, trade_volume: i32) -> MarketTradeGood {
+    MarketTradeGood {
+        symbol: symbol.to_string(),
+        good_type: MarketTradeGoodType::Exchange,
+        trade_volume,
+        supply: SupplyLevel::Moderate,
+        activity: None,
+        purchase_price,
+        sell_price,
+    }
+}
+
+/// Market as seen with a ship present, listing only its trade goods
+pub fn market(symbol: &str, trade_goods: Vec<MarketTradeGood>) -> Market {
+    Market {
+        symbol: symbol.to_string(),
+        exports: Vec::new(),
+        imports: Vec::new(),
+        exchange: Vec::new(),
+        transactions: None,
+        trade_goods: Some(trade_goods),
+    }
+}
+
+/// Response of the market endpoint
+pub fn market_response(market: &Market) -> MockResponse {
+    MockResponse::json(200, serde_json::json!({"data": market}))
+}
+
+/// Response of the purchase and sell endpoints at `X1-DF55-20250Z`
+///
+/// `cargo` is what the 15-unit hold holds after the trade.
+pub fn trade_response(
+    kind: &str,
+    trade_symbol: &str,
+    units: i32,
+    price_per_unit: i64,
+    cargo: &[(&str, i32)],
+    credits: i64
+) -> MockResponse {
+    let inventory: Vec<_> = cargo
+        .iter()
+        .map(|(symbol, units)| serde_json::json!({"symbol": symbol, "name": symbol, "description": "", "units": units}))
+        .collect();
+
+    MockResponse::json(201, serde_json::json!({
+        "data": {
+            "agent": {"symbol": "AGENT", "headquarters": "X1-DF55-A1", "credits": credits, "startingFaction": "COSMIC"},
+            "cargo": {"capacity": 15, "units": cargo.iter().map(|(_, units)| units).sum::<i32>(), "inventory": inventory},
+            "transaction": {
+                "waypointSymbol": "X1-DF55-20250Z",
+                "shipSymbol": "SHIP-1",
+                "tradeSymbol": trade_symbol,
+                "type": kind,
+                "units": units,
+                "pricePerUnit": price_per_unit,
+                "totalPrice": price_per_unit * units as i64,
+                "timestamp": "2026-10-16T12:00:00.000Z"
+            }
+        }
+    }))
+}
+
+/// Response of the refuel endpoint, leaving `fuel` in a 400-unit tank
+pub fn refuel_response(fuel: i32) -> MockResponse {
+    MockResponse::json(200, serde_json::json!({
+        "data": {
+            "agent": {"symbol": "AGENT", "headquarters": "X1-DF55-A1", "credits": 950, "startingFaction": "COSMIC"},
+            "fuel": {"current": fuel, "capacity": 400},
+            "transaction": {
+                "waypointSymbol": "X1-DF55-20250Z",
+                "shipSymbol": "SHIP-1",
+                "tradeSymbol": "FUEL",
+                "type": "PURCHASE",
+                "units": 1,
+                "pricePerUnit": 50,
+                "totalPrice": 50,
+                "timestamp": "2026-10-16T12:00:00.000Z"
+            }
+        }
+    }))
+}
+
+/// Response of the orbit, dock, navigate and flight mode endpoints, with 320 units of fuel left
+pub fn nav_response(status: &str, waypoint_symbol: &str, arrival: &str) -> MockResponse {
+    let mut nav = ship_json("SHIP-1")["nav"].clone();
+    nav["status"] = serde_json::json!(status);
+    nav["waypointSymbol"] = serde_json::json!(waypoint_symbol);
+    nav["route"]["arrival"] = serde_json::json!(arrival);
+    MockResponse::json(200, serde_json::json!({
+        "data": {"nav": nav, "fuel": {"current": 320, "capacity": 400}}
+    }))
+}
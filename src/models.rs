@@ -0,0 +1,880 @@
+//! Typed models for SpaceTraders API responses
+//!
+//! Field names follow the official OpenAPI spec; serde renames them from
+//! camelCase. Fields the spec marks optional are `Option`s or default to empty.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// Envelope around every single-object response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiResponse<T> {
+    pub data: T,
+}
+
+/// Envelope around every list response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaginatedResponse<T> {
+    pub data: Vec<T>,
+    pub meta: Meta,
+}
+
+/// Pagination details of a list response
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Meta {
+    pub total: u32,
+    pub page: u32,
+    pub limit: u32,
+}
+
+/// Returns the system symbol of a waypoint, e.g. `X1-DF55` for `X1-DF55-20250Z`
+pub fn system_symbol_of(waypoint_symbol: &str) -> String {
+    waypoint_symbol
+        .rsplit_once('-')
+        .map(|(system, _)| system)
+        .unwrap_or(waypoint_symbol)
+        .to_string()
+}
+
+/// Converts an API timestamp such as `2026-10-16T12:00:00.000Z` to Unix seconds
+pub fn unix_timestamp(timestamp: &str) -> Option<u64> {
+    let parsed = chrono::DateTime::parse_from_rfc3339(timestamp).ok()?;
+    u64::try_from(parsed.timestamp()).ok()
+}
+
+/// Time left until an API timestamp, to the millisecond; zero if it has passed or does not parse
+pub fn time_until(timestamp: &str) -> Duration {
+    chrono::DateTime::parse_from_rfc3339(timestamp)
+        .ok()
+        .and_then(|time| (time.with_timezone(&chrono::Utc) - chrono::Utc::now()).to_std().ok())
+        .unwrap_or_default()
+}
+
+/// Agent details
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Agent {
+    #[serde(default)]
+    pub account_id: Option<String>,
+    pub symbol: String,
+    pub headquarters: String,
+    pub credits: i64,
+    pub starting_faction: String,
+    #[serde(default)]
+    pub ship_count: u32,
+}
+
+/// Data returned when registering a new agent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Register {
+    pub agent: Agent,
+    pub token: String,
+    #[serde(default)]
+    pub contract: Option<Contract>,
+}
+
+/// Contract details
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Contract {
+    pub id: String,
+    pub faction_symbol: String,
+    #[serde(rename = "type")]
+    pub contract_type: ContractType,
+    pub terms: ContractTerms,
+    pub accepted: bool,
+    pub fulfilled: bool,
+    #[serde(default)]
+    pub expiration: Option<String>,
+    #[serde(default)]
+    pub deadline_to_accept: Option<String>,
+}
+
+/// Kind of work a contract asks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ContractType {
+    Procurement,
+    Transport,
+    Shuttle,
+}
+
+impl ContractType {
+    /// Returns the API name of the contract type
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ContractType::Procurement => "PROCUREMENT",
+            ContractType::Transport => "TRANSPORT",
+            ContractType::Shuttle => "SHUTTLE",
+        }
+    }
+}
+
+/// Terms to fulfill a contract
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContractTerms {
+    pub deadline: String,
+    pub payment: ContractPayment,
+    #[serde(default)]
+    pub deliver: Vec<ContractDeliverGood>,
+}
+
+/// Payments for accepting and fulfilling a contract
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContractPayment {
+    pub on_accepted: i64,
+    pub on_fulfilled: i64,
+}
+
+/// Goods to deliver for a contract
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContractDeliverGood {
+    pub trade_symbol: String,
+    pub destination_symbol: String,
+    pub units_required: i32,
+    pub units_fulfilled: i32,
+}
+
+/// Ship details
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Ship {
+    pub symbol: String,
+    pub registration: ShipRegistration,
+    pub nav: ShipNav,
+    pub crew: ShipCrew,
+    pub frame: ShipFrame,
+    pub reactor: ShipReactor,
+    pub engine: ShipEngine,
+    pub cooldown: Cooldown,
+    #[serde(default)]
+    pub modules: Vec<ShipModule>,
+    #[serde(default)]
+    pub mounts: Vec<ShipMount>,
+    pub cargo: ShipCargo,
+    pub fuel: ShipFuel,
+}
+
+/// Public registration information of a ship
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShipRegistration {
+    pub name: String,
+    pub faction_symbol: String,
+    pub role: String,
+}
+
+/// Navigation information of a ship
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShipNav {
+    pub system_symbol: String,
+    pub waypoint_symbol: String,
+    pub route: ShipNavRoute,
+    pub status: ShipNavStatus,
+    pub flight_mode: ShipNavFlightMode,
+}
+
+/// Route of a ship's last or current journey
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShipNavRoute {
+    pub destination: ShipNavRouteWaypoint,
+    pub origin: ShipNavRouteWaypoint,
+    pub departure_time: String,
+    pub arrival: String,
+}
+
+/// End point of a ship's route
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShipNavRouteWaypoint {
+    pub symbol: String,
+    #[serde(rename = "type")]
+    pub waypoint_type: String,
+    pub system_symbol: String,
+    pub x: i32,
+    pub y: i32,
+}
+
+/// Whether a ship is docked, in orbit or travelling
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ShipNavStatus {
+    InTransit,
+    InOrbit,
+    Docked,
+}
+
+/// Flight mode, trading fuel for speed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ShipNavFlightMode {
+    Drift,
+    Stealth,
+    Cruise,
+    Burn,
+}
+
+/// Nav and fuel returned by orbit, dock, navigate, warp and flight mode changes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShipNavUpdate {
+    pub nav: ShipNav,
+    #[serde(default)]
+    pub fuel: Option<ShipFuel>,
+}
+
+impl ShipNavUpdate {
+    /// Unix time the ship arrives at its destination
+    pub fn arrival(&self) -> Option<u64> {
+        unix_timestamp(&self.nav.route.arrival)
+    }
+}
+
+/// Crew of a ship
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShipCrew {
+    pub current: i32,
+    pub required: i32,
+    pub capacity: i32,
+    pub rotation: String,
+    pub morale: i32,
+    pub wages: i32,
+}
+
+/// Power, crew and slot requirements of a ship component
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShipRequirements {
+    #[serde(default)]
+    pub power: Option<i32>,
+    #[serde(default)]
+    pub crew: Option<i32>,
+    #[serde(default)]
+    pub slots: Option<i32>,
+}
+
+/// Frame of a ship
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShipFrame {
+    pub symbol: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub condition: Option<f64>,
+    #[serde(default)]
+    pub integrity: Option<f64>,
+    pub module_slots: i32,
+    pub mounting_points: i32,
+    pub fuel_capacity: i32,
+    #[serde(default)]
+    pub requirements: ShipRequirements,
+}
+
+/// Reactor of a ship
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShipReactor {
+    pub symbol: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub condition: Option<f64>,
+    #[serde(default)]
+    pub integrity: Option<f64>,
+    pub power_output: i32,
+    #[serde(default)]
+    pub requirements: ShipRequirements,
+}
+
+/// Engine of a ship
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShipEngine {
+    pub symbol: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub condition: Option<f64>,
+    #[serde(default)]
+    pub integrity: Option<f64>,
+    pub speed: i32,
+    #[serde(default)]
+    pub requirements: ShipRequirements,
+}
+
+/// Module installed in a ship
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShipModule {
+    pub symbol: String,
+    #[serde(default)]
+    pub capacity: Option<i32>,
+    #[serde(default)]
+    pub range: Option<i32>,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub requirements: ShipRequirements,
+}
+
+/// Mount installed on a ship
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShipMount {
+    pub symbol: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub strength: Option<i32>,
+    #[serde(default)]
+    pub deposits: Option<Vec<String>>,
+    #[serde(default)]
+    pub requirements: ShipRequirements,
+}
+
+/// Cargo hold of a ship
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShipCargo {
+    pub capacity: i32,
+    pub units: i32,
+    #[serde(default)]
+    pub inventory: Vec<ShipCargoItem>,
+}
+
+/// Goods of one type in a ship's cargo hold
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShipCargoItem {
+    pub symbol: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub units: i32,
+}
+
+/// Fuel tank of a ship
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShipFuel {
+    pub current: i32,
+    pub capacity: i32,
+    #[serde(default)]
+    pub consumed: Option<ShipFuelConsumed>,
+}
+
+/// Fuel used by the ship's last journey
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShipFuelConsumed {
+    pub amount: i32,
+    pub timestamp: String,
+}
+
+/// Cooldown of a ship's reactor after an action such as surveying or extracting
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Cooldown {
+    pub ship_symbol: String,
+    pub total_seconds: u64,
+    pub remaining_seconds: u64,
+    #[serde(default)]
+    pub expiration: Option<String>,
+}
+
+impl Cooldown {
+    /// Time left until the cooldown ends
+    ///
+    /// Uses the exact expiration timestamp when present, since
+    /// `remaining_seconds` is rounded to whole seconds.
+    pub fn remaining(&self) -> Duration {
+        let expiration = self
+            .expiration
+            .as_deref()
+            .and_then(|e| chrono::DateTime::parse_from_rfc3339(e).ok());
+
+        match expiration {
+            Some(expiration) => (expiration.with_timezone(&chrono::Utc) - chrono::Utc::now())
+                .to_std()
+                .unwrap_or_default(),
+            None => Duration::from_secs(self.remaining_seconds),
+        }
+    }
+}
+
+/// Waypoint details
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Waypoint {
+    pub symbol: String,
+    #[serde(rename = "type")]
+    pub waypoint_type: String,
+    pub system_symbol: String,
+    pub x: i32,
+    pub y: i32,
+    #[serde(default)]
+    pub orbitals: Vec<WaypointOrbital>,
+    #[serde(default)]
+    pub orbits: Option<String>,
+    #[serde(default)]
+    pub faction: Option<WaypointFaction>,
+    #[serde(default)]
+    pub traits: Vec<WaypointTrait>,
+    #[serde(default)]
+    pub modifiers: Vec<WaypointModifier>,
+    #[serde(default)]
+    pub chart: Option<Chart>,
+    #[serde(default)]
+    pub is_under_construction: bool,
+}
+
+impl Waypoint {
+    /// Whether the waypoint has a trait, e.g. `MARKETPLACE`
+    pub fn has_trait(&self, symbol: &str) -> bool {
+        self.traits.iter().any(|t| t.symbol == symbol)
+    }
+}
+
+/// Waypoint orbiting another one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WaypointOrbital {
+    pub symbol: String,
+}
+
+/// Faction controlling a waypoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WaypointFaction {
+    pub symbol: String,
+}
+
+/// Trait of a waypoint, such as a marketplace or a mineral deposit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WaypointTrait {
+    pub symbol: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+/// Temporary modifier of a waypoint, such as unstable composition
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WaypointModifier {
+    pub symbol: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+/// Who charted a waypoint and when
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Chart {
+    #[serde(default)]
+    pub waypoint_symbol: Option<String>,
+    #[serde(default)]
+    pub submitted_by: Option<String>,
+    #[serde(default)]
+    pub submitted_on: Option<String>,
+}
+
+/// Server status returned by the API root
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerStatus {
+    pub status: String,
+    pub version: String,
+    /// Date of the last universe reset, e.g. `2026-10-11`
+    pub reset_date: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+/// System details
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct System {
+    pub symbol: String,
+    pub sector_symbol: String,
+    #[serde(rename = "type")]
+    pub system_type: String,
+    pub x: i32,
+    pub y: i32,
+    #[serde(default)]
+    pub waypoints: Vec<SystemWaypoint>,
+    #[serde(default)]
+    pub factions: Vec<SystemFaction>,
+}
+
+/// Waypoint summary embedded in a system
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemWaypoint {
+    pub symbol: String,
+    #[serde(rename = "type")]
+    pub waypoint_type: String,
+    pub x: i32,
+    pub y: i32,
+    #[serde(default)]
+    pub orbitals: Vec<WaypointOrbital>,
+    #[serde(default)]
+    pub orbits: Option<String>,
+}
+
+/// Faction present in a system
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SystemFaction {
+    pub symbol: String,
+}
+
+/// Survey of an asteroid, as returned by and submitted back to the API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Survey {
+    pub signature: String,
+    pub symbol: String,
+    pub deposits: Vec<SurveyDeposit>,
+    pub expiration: String,
+    pub size: SurveySize,
+}
+
+impl Survey {
+    /// Unix time the survey expires
+    pub fn expires_at(&self) -> Option<u64> {
+        unix_timestamp(&self.expiration)
+    }
+}
+
+/// Data returned by the survey endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SurveyResult {
+    pub cooldown: Cooldown,
+    pub surveys: Vec<Survey>,
+}
+
+/// Resource found by a survey
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SurveyDeposit {
+    pub symbol: String,
+}
+
+/// Size of a surveyed deposit, which determines how many extractions it lasts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SurveySize {
+    Small,
+    Moderate,
+    Large,
+}
+
+/// Data returned by the purchase and sell endpoints
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeResult {
+    pub agent: Agent,
+    pub cargo: ShipCargo,
+    pub transaction: MarketTransaction,
+}
+
+/// Data returned by the refuel endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RefuelResult {
+    pub agent: Agent,
+    pub fuel: ShipFuel,
+    pub transaction: MarketTransaction,
+}
+
+/// Data returned by the extract endpoints
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractionResult {
+    pub cooldown: Cooldown,
+    pub extraction: Extraction,
+    pub cargo: ShipCargo,
+}
+
+/// Resources extracted by a ship
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Extraction {
+    pub ship_symbol: String,
+    #[serde(rename = "yield")]
+    pub extraction_yield: ExtractionYield,
+}
+
+/// Good and amount produced by one extraction
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractionYield {
+    pub symbol: String,
+    pub units: i32,
+}
+
+/// Market details
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Market {
+    pub symbol: String,
+    #[serde(default)]
+    pub exports: Vec<TradeGood>,
+    #[serde(default)]
+    pub imports: Vec<TradeGood>,
+    #[serde(default)]
+    pub exchange: Vec<TradeGood>,
+    #[serde(default)]
+    pub transactions: Option<Vec<MarketTransaction>>,
+    #[serde(default)]
+    pub trade_goods: Option<Vec<MarketTradeGood>>,
+}
+
+/// Good listed by a market
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeGood {
+    pub symbol: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+/// Price and availability of a good, only visible with a ship at the market
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketTradeGood {
+    pub symbol: String,
+    #[serde(rename = "type")]
+    pub good_type: MarketTradeGoodType,
+    pub trade_volume: i32,
+    pub supply: SupplyLevel,
+    #[serde(default)]
+    pub activity: Option<ActivityLevel>,
+    pub purchase_price: i64,
+    pub sell_price: i64,
+}
+
+/// Whether a market exports, imports or exchanges a good
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum MarketTradeGoodType {
+    Export,
+    Import,
+    Exchange,
+}
+
+/// Supply of a good at a market
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SupplyLevel {
+    Scarce,
+    Limited,
+    Moderate,
+    High,
+    Abundant,
+}
+
+/// Trading activity of a good at a market
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ActivityLevel {
+    Weak,
+    Growing,
+    Strong,
+    Restricted,
+}
+
+/// Purchase or sale made at a market
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketTransaction {
+    pub waypoint_symbol: String,
+    pub ship_symbol: String,
+    pub trade_symbol: String,
+    #[serde(rename = "type")]
+    pub transaction_type: TransactionType,
+    pub units: i32,
+    pub price_per_unit: i64,
+    pub total_price: i64,
+    pub timestamp: String,
+}
+
+/// Direction of a market transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TransactionType {
+    Purchase,
+    Sell,
+}
+
+/// Shipyard details
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Shipyard {
+    pub symbol: String,
+    #[serde(default)]
+    pub ship_types: Vec<ShipyardShipType>,
+    #[serde(default)]
+    pub transactions: Option<Vec<ShipyardTransaction>>,
+    #[serde(default)]
+    pub ships: Option<Vec<ShipyardShip>>,
+    pub modifications_fee: i64,
+}
+
+/// Ship type sold by a shipyard
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShipyardShipType {
+    #[serde(rename = "type")]
+    pub ship_type: String,
+}
+
+/// Ship purchase made at a shipyard
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShipyardTransaction {
+    pub waypoint_symbol: String,
+    pub ship_type: String,
+    pub price: i64,
+    pub agent_symbol: String,
+    pub timestamp: String,
+}
+
+/// Ship for sale, only visible with a ship at the shipyard
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShipyardShip {
+    #[serde(rename = "type")]
+    pub ship_type: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub supply: Option<SupplyLevel>,
+    #[serde(default)]
+    pub activity: Option<ActivityLevel>,
+    pub purchase_price: i64,
+    pub frame: ShipFrame,
+    pub reactor: ShipReactor,
+    pub engine: ShipEngine,
+    #[serde(default)]
+    pub modules: Vec<ShipModule>,
+    #[serde(default)]
+    pub mounts: Vec<ShipMount>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_system_symbol_of_waypoint() {
+        assert_eq!(system_symbol_of("X1-DF55-20250Z"), "X1-DF55");
+        assert_eq!(system_symbol_of("X1"), "X1");
+    }
+
+    #[test]
+    fn test_unix_timestamp_parses_api_format() {
+        assert_eq!(unix_timestamp("2026-10-16T12:00:00.000Z"), Some(1_792_152_000));
+        assert_eq!(unix_timestamp("not a timestamp"), None);
+    }
+
+    #[test]
+    fn test_time_until_keeps_fractional_seconds() {
+        let arrival = (chrono::Utc::now() + chrono::Duration::milliseconds(30_750)).to_rfc3339();
+        let remaining = time_until(&arrival);
+
+        assert!(remaining > Duration::from_millis(30_500) && remaining <= Duration::from_millis(30_750));
+        assert_eq!(time_until("2020-01-01T00:00:00.000Z"), Duration::ZERO);
+        assert_eq!(time_until("not a timestamp"), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_paginated_response_parses_meta() {
+        let response: PaginatedResponse<SystemFaction> = serde_json::from_value(json!({
+            "data": [{"symbol": "COSMIC"}],
+            "meta": {"total": 21, "page": 1, "limit": 20}
+        }))
+        .unwrap();
+
+        assert_eq!(response.data.len(), 1);
+        assert_eq!(response.meta, Meta { total: 21, page: 1, limit: 20 });
+    }
+
+    #[test]
+    fn test_contract_parses() {
+        let contract: Contract = serde_json::from_value(json!({
+            "id": "contract-123",
+            "factionSymbol": "COSMIC",
+            "type": "PROCUREMENT",
+            "terms": {
+                "deadline": "2026-10-23T00:00:00.000Z",
+                "payment": {"onAccepted": 1000, "onFulfilled": 5000},
+                "deliver": [{
+                    "tradeSymbol": "IRON_ORE",
+                    "destinationSymbol": "X1-ABCD-1234",
+                    "unitsRequired": 50,
+                    "unitsFulfilled": 0
+                }]
+            },
+            "accepted": false,
+            "fulfilled": false,
+            "deadlineToAccept": "2026-10-17T00:00:00.000Z"
+        }))
+        .unwrap();
+
+        assert_eq!(contract.contract_type, ContractType::Procurement);
+        assert_eq!(contract.terms.payment.on_fulfilled, 5000);
+        assert_eq!(contract.terms.deliver[0].units_required, 50);
+    }
+
+    #[test]
+    fn test_missing_required_field_is_an_error() {
+        let result = serde_json::from_value::<Agent>(json!({"symbol": "AGENT-123"}));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_market_trade_good_parses() {
+        let good: MarketTradeGood = serde_json::from_value(json!({
+            "symbol": "IRON_ORE",
+            "type": "IMPORT",
+            "tradeVolume": 60,
+            "supply": "MODERATE",
+            "activity": "WEAK",
+            "purchasePrice": 52,
+            "sellPrice": 48
+        }))
+        .unwrap();
+
+        assert_eq!(good.good_type, MarketTradeGoodType::Import);
+        assert_eq!(good.supply, SupplyLevel::Moderate);
+        assert_eq!(good.activity, Some(ActivityLevel::Weak));
+    }
+
+    #[test]
+    fn test_survey_round_trips_for_submission() {
+        let raw = json!({
+            "signature": "X1-DF55-20250Z-7A1B2C",
+            "symbol": "X1-DF55-20250Z",
+            "deposits": [{"symbol": "IRON_ORE"}, {"symbol": "IRON_ORE"}, {"symbol": "COPPER_ORE"}],
+            "expiration": "2026-10-16T12:34:56.789Z",
+            "size": "MODERATE"
+        });
+        let survey: Survey = serde_json::from_value(raw.clone()).unwrap();
+
+        assert_eq!(survey.size, SurveySize::Moderate);
+        assert_eq!(survey.expires_at(), Some(1_792_154_096));
+        assert_eq!(serde_json::to_value(&survey).unwrap(), raw);
+    }
+}
@@ -0,0 +1,154 @@
+//! Ship navigation: orbit, dock, navigate, warp and flight mode
+
+use serde::Serialize;
+
+use crate::client::ApiClient;
+use crate::error::SpaceTradersError;
+use crate::models::{ApiResponse, ShipNavFlightMode, ShipNavUpdate};
+use crate::shared_storage::SharedStatusStorage;
+
+/// Body of navigate and warp requests
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NavigateRequest<'a> {
+    waypoint_symbol: &'a str,
+}
+
+/// Body of flight mode changes
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FlightModeRequest {
+    flight_mode: ShipNavFlightMode,
+}
+
+/// Moves a docked ship into orbit
+pub async fn orbit_ship(
+    client: &ApiClient,
+    storage: &SharedStatusStorage,
+    ship_symbol: &str
+) -> Result<ShipNavUpdate, SpaceTradersError> {
+    let response: ApiResponse<ShipNavUpdate> = client.post(&format!("/my/ships/{}/orbit", ship_symbol)).await?;
+    storage.record_navigation(ship_symbol, &response.data).await;
+    Ok(response.data)
+}
+
+/// Docks an orbiting ship at its current waypoint
+pub async fn dock_ship(
+    client: &ApiClient,
+    storage: &SharedStatusStorage,
+    ship_symbol: &str
+) -> Result<ShipNavUpdate, SpaceTradersError> {
+    let response: ApiResponse<ShipNavUpdate> = client.post(&format!("/my/ships/{}/dock", ship_symbol)).await?;
+    storage.record_navigation(ship_symbol, &response.data).await;
+    Ok(response.data)
+}
+
+/// Sends an orbiting ship to a waypoint in its current system
+pub async fn navigate_ship(
+    client: &ApiClient,
+    storage: &SharedStatusStorage,
+    ship_symbol: &str,
+    waypoint_symbol: &str
+) -> Result<ShipNavUpdate, SpaceTradersError> {
+    let request = NavigateRequest { waypoint_symbol };
+    let response: ApiResponse<ShipNavUpdate> = client
+        .post_json(&format!("/my/ships/{}/navigate", ship_symbol), &request)
+        .await?;
+
+    println!(
+        "{} navigating to {}, arriving at {}",
+        ship_symbol, waypoint_symbol, response.data.nav.route.arrival
+    );
+
+    storage.record_navigation(ship_symbol, &response.data).await;
+    Ok(response.data)
+}
+
+/// Warps an orbiting ship to a waypoint in another system
+pub async fn warp_ship(
+    client: &ApiClient,
+    storage: &SharedStatusStorage,
+    ship_symbol: &str,
+    waypoint_symbol: &str
+) -> Result<ShipNavUpdate, SpaceTradersError> {
+    let request = NavigateRequest { waypoint_symbol };
+    let response: ApiResponse<ShipNavUpdate> = client
+        .post_json(&format!("/my/ships/{}/warp", ship_symbol), &request)
+        .await?;
+
+    println!(
+        "{} warping to {}, arriving at {}",
+        ship_symbol, waypoint_symbol, response.data.nav.route.arrival
+    );
+
+    storage.record_navigation(ship_symbol, &response.data).await;
+    Ok(response.data)
+}
+
+/// Changes the flight mode used for the ship's next journeys
+pub async fn set_flight_mode(
+    client: &ApiClient,
+    storage: &SharedStatusStorage,
+    ship_symbol: &str,
+    flight_mode: ShipNavFlightMode
+) -> Result<ShipNavUpdate, SpaceTradersError> {
+    let request = FlightModeRequest { flight_mode };
+    let response: ApiResponse<ShipNavUpdate> = client
+        .patch_json(&format!("/my/ships/{}/nav", ship_symbol), &request)
+        .await?;
+    storage.record_navigation(ship_symbol, &response.data).await;
+    Ok(response.data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_server::{MockServer, nav_response};
+    use crate::status_storage::ShipStatusType;
+
+    #[tokio::test]
+    async fn test_navigate_marks_ship_traveling_until_arrival() {
+        let server = MockServer::start().await;
+        server.route("POST", "/my/ships/SHIP-1/navigate", vec![nav_response("IN_TRANSIT", "X1-DF55-B7", "2099-01-01T00:00:00.000Z")]);
+
+        let client = ApiClient::with_base_url(server.base_url(), Some("test_token"));
+        let storage = SharedStatusStorage::default();
+        let update = navigate_ship(&client, &storage, "SHIP-1", "X1-DF55-B7").await.unwrap();
+
+        assert_eq!(update.fuel.as_ref().map(|f| f.current), Some(320));
+        assert_eq!(update.arrival(), Some(4_070_908_800));
+        assert_eq!(server.requests()[0].body, r#"{"waypointSymbol":"X1-DF55-B7"}"#);
+
+        let status = storage.get_status("SHIP-1").await.unwrap();
+        assert_eq!(status.status_type, ShipStatusType::Traveling);
+        assert_eq!(status.expires_at, Some(4_070_908_800));
+        assert_eq!(status.location, "X1-DF55-B7");
+        assert_eq!(status.fuel, 320);
+    }
+
+    #[tokio::test]
+    async fn test_dock_marks_ship_idle() {
+        let server = MockServer::start().await;
+        server.route("POST", "/my/ships/SHIP-1/dock", vec![nav_response("DOCKED", "X1-DF55-B7", "2026-10-16T12:00:00.000Z")]);
+
+        let client = ApiClient::with_base_url(server.base_url(), Some("test_token"));
+        let storage = SharedStatusStorage::default();
+        dock_ship(&client, &storage, "SHIP-1").await.unwrap();
+
+        assert_eq!(storage.get_status("SHIP-1").await.unwrap().status_type, ShipStatusType::Idle);
+    }
+
+    #[tokio::test]
+    async fn test_set_flight_mode_sends_mode() {
+        let server = MockServer::start().await;
+        server.route("PATCH", "/my/ships/SHIP-1/nav", vec![nav_response("IN_ORBIT", "X1-DF55-B7", "2026-10-16T12:00:00.000Z")]);
+
+        let client = ApiClient::with_base_url(server.base_url(), Some("test_token"));
+        let storage = SharedStatusStorage::default();
+        set_flight_mode(&client, &storage, "SHIP-1", ShipNavFlightMode::Drift).await.unwrap();
+
+        let request = &server.requests()[0];
+        assert_eq!(request.method, "PATCH");
+        assert_eq!(request.body, r#"{"flightMode":"DRIFT"}"#);
+    }
+}
@@ -0,0 +1,119 @@
+//! Pagination over SpaceTraders list endpoints
+
+use std::marker::PhantomData;
+
+use serde::de::DeserializeOwned;
+
+use crate::client::ApiClient;
+use crate::error::SpaceTradersError;
+use crate::models::PaginatedResponse;
+
+/// Largest page size the API accepts
+pub const MAX_PAGE_LIMIT: u32 = 20;
+
+/// Walks a list endpoint page by page, following `meta.total/page/limit`
+pub struct Paginator<'a, T> {
+    client: &'a ApiClient,
+    path: String,
+    limit: u32,
+    next_page: u32,
+    done: bool,
+    _item: PhantomData<T>,
+}
+
+impl<'a, T: DeserializeOwned> Paginator<'a, T> {
+    /// Creates a paginator that requests pages of `MAX_PAGE_LIMIT` items
+    pub fn new(client: &'a ApiClient, path: &str) -> Self {
+        Self {
+            client,
+            path: path.to_string(),
+            limit: MAX_PAGE_LIMIT,
+            next_page: 1,
+            done: false,
+            _item: PhantomData,
+        }
+    }
+
+    /// Fetches the next page, or `None` once every item has been returned
+    pub async fn next_page(&mut self) -> Result<Option<Vec<T>>, SpaceTradersError> {
+        if self.done {
+            return Ok(None);
+        }
+
+        let separator = if self.path.contains('?') { '&' } else { '?' };
+        let path = format!("{}{}page={}&limit={}", self.path, separator, self.next_page, self.limit);
+        let response: PaginatedResponse<T> = self.client.get(&path).await?;
+
+        let meta = response.meta;
+        if response.data.is_empty() || meta.page * meta.limit >= meta.total {
+            self.done = true;
+        }
+        self.next_page += 1;
+
+        Ok(Some(response.data))
+    }
+
+    /// Fetches every remaining page and returns all items
+    pub async fn collect_all(mut self) -> Result<Vec<T>, SpaceTradersError> {
+        let mut items = Vec::new();
+
+        while let Some(page) = self.next_page().await? {
+            items.extend(page);
+        }
+
+        Ok(items)
+    }
+}
+
+impl ApiClient {
+    /// Returns a paginator over a list endpoint such as `/my/ships`
+    pub fn paginate<T: DeserializeOwned>(&self, path: &str) -> Paginator<'_, T> {
+        Paginator::new(self, path)
+    }
+
+    /// Fetches every page of a list endpoint
+    pub async fn get_all<T: DeserializeOwned>(&self, path: &str) -> Result<Vec<T>, SpaceTradersError> {
+        self.paginate(path).collect_all().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_server::{MockResponse, MockServer};
+    use crate::models::SystemFaction;
+    use serde_json::json;
+
+    fn faction_page(symbols: &[&str], page: u32, total: u32) -> MockResponse {
+        let data: Vec<_> = symbols.iter().map(|s| json!({"symbol": s})).collect();
+        MockResponse::json(200, json!({"data": data, "meta": {"total": total, "page": page, "limit": 20}}))
+    }
+
+    #[tokio::test]
+    async fn test_get_all_follows_every_page() {
+        let server = MockServer::start().await;
+        let first: Vec<String> = (0..20).map(|i| format!("FACTION-{}", i)).collect();
+        let first: Vec<&str> = first.iter().map(String::as_str).collect();
+        server.route("GET", "/factions?page=1&limit=20", vec![faction_page(&first, 1, 21)]);
+        server.route("GET", "/factions?page=2&limit=20", vec![faction_page(&["FACTION-20"], 2, 21)]);
+
+        let client = ApiClient::with_base_url(server.base_url(), Some("test_token"));
+        let factions: Vec<SystemFaction> = client.get_all("/factions").await.unwrap();
+
+        assert_eq!(factions.len(), 21);
+        assert_eq!(factions[20].symbol, "FACTION-20");
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_existing_query_is_kept() {
+        let server = MockServer::start().await;
+        server.route("GET", "/systems/X1-DF55/waypoints?traits=MARKETPLACE&page=1&limit=20", vec![faction_page(&[], 1, 0)]);
+
+        let client = ApiClient::with_base_url(server.base_url(), Some("test_token"));
+        let mut pages = client.paginate::<SystemFaction>("/systems/X1-DF55/waypoints?traits=MARKETPLACE");
+
+        assert_eq!(pages.next_page().await.unwrap().map(|p| p.len()), Some(0));
+        assert!(pages.next_page().await.unwrap().is_none());
+    }
+}
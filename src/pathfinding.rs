@@ -0,0 +1,203 @@
+//! Fuel-aware flight planning between waypoints of a system
+//!
+//! Plans are found with Dijkstra's algorithm over (waypoint, fuel on board)
+//! states, so a leg is only taken if the tank can pay for it. Marketplaces
+//! selling fuel fill the tank before departing.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+use crate::distance::{Waypoint, fuel_cost, travel_seconds};
+use crate::models::{Market, Ship, ShipNavFlightMode};
+
+/// Waypoint index and fuel on arrival
+type State = (usize, i32);
+
+/// Flight modes considered for every leg
+const LEG_MODES: [ShipNavFlightMode; 3] = [ShipNavFlightMode::Burn, ShipNavFlightMode::Cruise, ShipNavFlightMode::Drift];
+
+/// What the planner needs to know about the ship flying
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FlightProfile {
+    pub speed: i32,
+    /// Zero for ships that do not use fuel
+    pub fuel_capacity: i32,
+    /// Fuel on board at the start
+    pub fuel: i32,
+}
+
+impl From<&Ship> for FlightProfile {
+    fn from(ship: &Ship) -> Self {
+        Self {
+            speed: ship.engine.speed,
+            fuel_capacity: ship.fuel.capacity,
+            fuel: ship.fuel.current,
+        }
+    }
+}
+
+/// One flight of a plan
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlightLeg {
+    pub from: String,
+    pub to: String,
+    pub mode: ShipNavFlightMode,
+    pub fuel: i32,
+    pub seconds: u64,
+    /// Fill the tank at `from` before departing
+    pub refuel_before: bool,
+}
+
+/// Fastest sequence of flights between two waypoints
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlightPlan {
+    pub legs: Vec<FlightLeg>,
+    pub total_seconds: u64,
+    pub fuel_used: i32,
+}
+
+/// Whether a market sells fuel
+pub fn sells_fuel(market: &Market) -> bool {
+    market.exports.iter().chain(&market.exchange).any(|good| good.symbol == "FUEL")
+        || market.trade_goods.iter().flatten().any(|good| good.symbol == "FUEL")
+}
+
+/// Plans the fastest flight from one waypoint to another
+///
+/// Every leg may fly in BURN, CRUISE or DRIFT mode, as long as the fuel on
+/// board covers it; waypoints in `refuel_stations` fill the tank before the
+/// next leg. Returns `None` if either waypoint is unknown or the destination
+/// cannot be reached.
+pub fn plan_flight(
+    waypoints: &[Waypoint],
+    refuel_stations: &HashSet<String>,
+    from: &str,
+    to: &str,
+    ship: &FlightProfile
+) -> Option<FlightPlan> {
+    let start = waypoints.iter().position(|w| w.symbol == from)?;
+    let goal = waypoints.iter().position(|w| w.symbol == to)?;
+    let start_fuel = ship.fuel.clamp(0, ship.fuel_capacity);
+
+    let mut best: HashMap<State, u64> = HashMap::from([((start, start_fuel), 0)]);
+    let mut previous: HashMap<State, (State, FlightLeg)> = HashMap::new();
+    let mut queue = BinaryHeap::from([Reverse((0, start, start_fuel))]);
+
+    while let Some(Reverse((seconds, node, fuel))) = queue.pop() {
+        if best.get(&(node, fuel)).is_some_and(|&known| known < seconds) {
+            continue;
+        }
+        if node == goal {
+            return Some(build_plan(&previous, (node, fuel), seconds));
+        }
+
+        let refuel = refuel_stations.contains(&waypoints[node].symbol) && fuel < ship.fuel_capacity;
+        let departure_fuel = if refuel { ship.fuel_capacity } else { fuel };
+
+        for (next, waypoint) in waypoints.iter().enumerate() {
+            if next == node {
+                continue;
+            }
+            let distance = waypoints[node].distance_to(waypoint);
+
+            for mode in LEG_MODES {
+                let leg_fuel = if ship.fuel_capacity == 0 { 0 } else { fuel_cost(distance, mode) };
+                if leg_fuel > departure_fuel {
+                    continue;
+                }
+                let leg_seconds = travel_seconds(distance, ship.speed, mode);
+                let state = (next, departure_fuel - leg_fuel);
+                let arrival = seconds + leg_seconds;
+                if best.get(&state).is_some_and(|&known| known <= arrival) {
+                    continue;
+                }
+
+                best.insert(state, arrival);
+                previous.insert(state, ((node, fuel), FlightLeg {
+                    from: waypoints[node].symbol.clone(),
+                    to: waypoint.symbol.clone(),
+                    mode,
+                    fuel: leg_fuel,
+                    seconds: leg_seconds,
+                    refuel_before: refuel,
+                }));
+                queue.push(Reverse((arrival, state.0, state.1)));
+            }
+        }
+    }
+
+    None
+}
+
+/// Walks the legs leading to `state` back to the start
+fn build_plan(
+    previous: &HashMap<State, (State, FlightLeg)>,
+    mut state: State,
+    total_seconds: u64
+) -> FlightPlan {
+    let mut legs = Vec::new();
+    while let Some((before, leg)) = previous.get(&state) {
+        legs.push(leg.clone());
+        state = *before;
+    }
+    legs.reverse();
+
+    let fuel_used = legs.iter().map(|leg| leg.fuel).sum();
+    FlightPlan { legs, total_seconds, fuel_used }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn system() -> Vec<Waypoint> {
+        vec![
+            Waypoint::new("X1-DF55-A".to_string(), 0, 0),
+            Waypoint::new("X1-DF55-B".to_string(), 60, 0),
+            Waypoint::new("X1-DF55-C".to_string(), 200, 0),
+        ]
+    }
+
+    fn ship(fuel: i32) -> FlightProfile {
+        FlightProfile { speed: 10, fuel_capacity: 150, fuel }
+    }
+
+    fn modes(plan: &FlightPlan) -> Vec<ShipNavFlightMode> {
+        plan.legs.iter().map(|leg| leg.mode).collect()
+    }
+
+    #[test]
+    fn test_plan_refuels_to_avoid_drifting() {
+        let stations = HashSet::from(["X1-DF55-B".to_string()]);
+        let plan = plan_flight(&system(), &stations, "X1-DF55-A", "X1-DF55-C", &ship(150)).unwrap();
+
+        // Burn to the station, fill up, then cruise the 140 units left
+        assert_eq!(modes(&plan), [ShipNavFlightMode::Burn, ShipNavFlightMode::Cruise]);
+        assert!(!plan.legs[0].refuel_before);
+        assert!(plan.legs[1].refuel_before);
+        assert_eq!(plan.total_seconds, 90 + 365);
+        assert_eq!(plan.fuel_used, 120 + 140);
+    }
+
+    #[test]
+    fn test_plan_without_stations_respects_fuel_budget() {
+        let plan = plan_flight(&system(), &HashSet::new(), "X1-DF55-A", "X1-DF55-C", &ship(150)).unwrap();
+
+        // Too far to cruise on one tank: drift the short hop to save fuel for the long one
+        assert_eq!(modes(&plan), [ShipNavFlightMode::Drift, ShipNavFlightMode::Cruise]);
+        assert_eq!(plan.total_seconds, 1515 + 365);
+        assert_eq!(plan.fuel_used, 141);
+
+        assert!(plan_flight(&system(), &HashSet::new(), "X1-DF55-A", "X1-DF55-C", &ship(0)).is_none());
+        assert!(plan_flight(&system(), &HashSet::new(), "X1-DF55-A", "X1-DF55-Z", &ship(150)).is_none());
+    }
+
+    #[test]
+    fn test_ships_without_tank_burn_everywhere() {
+        let probe = FlightProfile { speed: 3, fuel_capacity: 0, fuel: 0 };
+        let plan = plan_flight(&system(), &HashSet::new(), "X1-DF55-A", "X1-DF55-C", &probe).unwrap();
+
+        assert_eq!(modes(&plan), [ShipNavFlightMode::Burn]);
+        assert_eq!(plan.fuel_used, 0);
+    }
+}
@@ -0,0 +1,204 @@
+//! Time series of observed market prices
+//!
+//! Every time a market is read with a ship present its trade goods are
+//! appended here, so trade decisions can use prices of markets no ship is
+//! currently docked at.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::{ActivityLevel, MarketTradeGood, SupplyLevel};
+use crate::status_storage::unix_now;
+
+/// Number of observations kept per waypoint and good; older ones are dropped first
+pub const MAX_PRICE_POINTS: usize = 500;
+
+/// Which side of a market's prices to query
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PriceKind {
+    /// What the market charges when we buy
+    Purchase,
+    /// What the market pays when we sell
+    Sell,
+}
+
+/// One observation of a good at a market
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PricePoint {
+    /// Unix time the market was observed
+    pub observed_at: u64,
+    pub purchase_price: i64,
+    pub sell_price: i64,
+    pub trade_volume: i32,
+    pub supply: SupplyLevel,
+    pub activity: Option<ActivityLevel>,
+}
+
+impl PricePoint {
+    /// Price on one side of the market
+    pub fn price(&self, kind: PriceKind) -> i64 {
+        match kind {
+            PriceKind::Purchase => self.purchase_price,
+            PriceKind::Sell => self.sell_price,
+        }
+    }
+}
+
+/// Observed prices keyed by waypoint symbol, then trade symbol, oldest first
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PriceHistory {
+    series: HashMap<String, HashMap<String, Vec<PricePoint>>>,
+}
+
+impl PriceHistory {
+    /// Creates an empty history
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends every trade good of a market observed at `observed_at`
+    pub fn record(&mut self, waypoint_symbol: &str, observed_at: u64, goods: &[MarketTradeGood]) {
+        let market = self.series.entry(waypoint_symbol.to_string()).or_default();
+
+        for good in goods {
+            let points = market.entry(good.symbol.clone()).or_default();
+            points.push(PricePoint {
+                observed_at,
+                purchase_price: good.purchase_price,
+                sell_price: good.sell_price,
+                trade_volume: good.trade_volume,
+                supply: good.supply,
+                activity: good.activity,
+            });
+            if points.len() > MAX_PRICE_POINTS {
+                let excess = points.len() - MAX_PRICE_POINTS;
+                points.drain(..excess);
+            }
+        }
+    }
+
+    /// Every observation of a good at a market, oldest first
+    pub fn points(&self, waypoint_symbol: &str, trade_symbol: &str) -> &[PricePoint] {
+        self.series
+            .get(waypoint_symbol)
+            .and_then(|market| market.get(trade_symbol))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Every good observed at any market, without duplicates
+    pub fn trade_symbols(&self) -> Vec<&str> {
+        let mut symbols: Vec<&str> = self.series.values().flat_map(|market| market.keys()).map(String::as_str).collect();
+        symbols.sort_unstable();
+        symbols.dedup();
+        symbols
+    }
+
+    /// Markets that have been observed trading a good
+    pub fn markets_trading(&self, trade_symbol: &str) -> Vec<&str> {
+        self.series
+            .iter()
+            .filter(|(_, market)| market.contains_key(trade_symbol))
+            .map(|(waypoint_symbol, _)| waypoint_symbol.as_str())
+            .collect()
+    }
+
+    /// Most recent observation of a good at a market
+    pub fn latest(&self, waypoint_symbol: &str, trade_symbol: &str) -> Option<&PricePoint> {
+        self.points(waypoint_symbol, trade_symbol).last()
+    }
+
+    /// Most recent observation of every good seen at a market
+    pub fn latest_at(&self, waypoint_symbol: &str) -> Vec<(&str, &PricePoint)> {
+        self.series
+            .get(waypoint_symbol)
+            .into_iter()
+            .flatten()
+            .filter_map(|(trade_symbol, points)| Some((trade_symbol.as_str(), points.last()?)))
+            .collect()
+    }
+
+    /// Seconds since a good was last observed at a market
+    pub fn observation_age(&self, waypoint_symbol: &str, trade_symbol: &str) -> Option<u64> {
+        self.latest(waypoint_symbol, trade_symbol)
+            .map(|point| unix_now().saturating_sub(point.observed_at))
+    }
+
+    /// Mean price over the observations made at or after `since` (Unix time)
+    pub fn average_price(&self, waypoint_symbol: &str, trade_symbol: &str, kind: PriceKind, since: u64) -> Option<f64> {
+        let prices: Vec<i64> = self.prices_since(waypoint_symbol, trade_symbol, kind, since).collect();
+        if prices.is_empty() {
+            return None;
+        }
+        Some(prices.iter().sum::<i64>() as f64 / prices.len() as f64)
+    }
+
+    /// Lowest and highest price over the observations made at or after `since` (Unix time)
+    pub fn price_range(&self, waypoint_symbol: &str, trade_symbol: &str, kind: PriceKind, since: u64) -> Option<(i64, i64)> {
+        self.prices_since(waypoint_symbol, trade_symbol, kind, since)
+            .fold(None, |range, price| match range {
+                None => Some((price, price)),
+                Some((min, max)) => Some((min.min(price), max.max(price))),
+            })
+    }
+
+    /// Prices of the observations made at or after `since`
+    fn prices_since(
+        &self,
+        waypoint_symbol: &str,
+        trade_symbol: &str,
+        kind: PriceKind,
+        since: u64
+    ) -> impl Iterator<Item = i64> + '_ {
+        self.points(waypoint_symbol, trade_symbol)
+            .iter()
+            .filter(move |point| point.observed_at >= since)
+            .map(move |point| point.price(kind))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_server::trade_good;
+
+    fn good(symbol: &str, sell_price: i64, supply: SupplyLevel) -> MarketTradeGood {
+        MarketTradeGood { supply, ..trade_good(symbol, sell_price + 10, sell_price, 60) }
+    }
+
+    #[test]
+    fn test_window_queries_over_recorded_prices() {
+        let mut history = PriceHistory::new();
+        history.record("X1-DF55-A1", 1_000, &[good("IRON_ORE", 40, SupplyLevel::Scarce)]);
+        history.record("X1-DF55-A1", 2_000, &[good("IRON_ORE", 60, SupplyLevel::Limited)]);
+        history.record("X1-DF55-A1", 3_000, &[good("IRON_ORE", 50, SupplyLevel::Moderate), good("FUEL", 70, SupplyLevel::High)]);
+        history.record("X1-DF55-B2", 3_000, &[good("FUEL", 65, SupplyLevel::High)]);
+
+        let latest = history.latest("X1-DF55-A1", "IRON_ORE").unwrap();
+        assert_eq!((latest.observed_at, latest.sell_price, latest.supply), (3_000, 50, SupplyLevel::Moderate));
+
+        assert_eq!(history.average_price("X1-DF55-A1", "IRON_ORE", PriceKind::Sell, 0), Some(50.0));
+        assert_eq!(history.average_price("X1-DF55-A1", "IRON_ORE", PriceKind::Purchase, 2_000), Some(65.0));
+        assert_eq!(history.price_range("X1-DF55-A1", "IRON_ORE", PriceKind::Sell, 1_500), Some((50, 60)));
+        assert_eq!(history.price_range("X1-DF55-A1", "IRON_ORE", PriceKind::Sell, 5_000), None);
+        assert!(history.observation_age("X1-DF55-A1", "FUEL").unwrap() > 0);
+        assert_eq!(history.observation_age("X1-DF55-A1", "GOLD_ORE"), None);
+
+        let mut fuel_markets = history.markets_trading("FUEL");
+        fuel_markets.sort();
+        assert_eq!(fuel_markets, ["X1-DF55-A1", "X1-DF55-B2"]);
+    }
+
+    #[test]
+    fn test_history_keeps_most_recent_points() {
+        let mut history = PriceHistory::new();
+        for t in 0..(MAX_PRICE_POINTS as u64 + 10) {
+            history.record("X1-DF55-A1", t, &[good("IRON_ORE", 40, SupplyLevel::Scarce)]);
+        }
+
+        let points = history.points("X1-DF55-A1", "IRON_ORE");
+        assert_eq!(points.len(), MAX_PRICE_POINTS);
+        assert_eq!(points[0].observed_at, 10);
+    }
+}
@@ -0,0 +1,278 @@
+//! Client-side rate limiting for the SpaceTraders API
+//!
+//! The API allows a steady rate of requests per second plus a burst pool that
+//! can be drawn on when the steady bucket is empty. The burst pool refills in
+//! full once its window has elapsed since it was first drawn on.
+
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+/// Limits documented by the SpaceTraders API
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimitConfig {
+    pub requests_per_second: f64,
+    pub burst_limit: u32,
+    pub burst_window: Duration,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            requests_per_second: 2.0,
+            burst_limit: 30,
+            burst_window: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Snapshot of the remaining request budget, for diagnostics
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimitBudget {
+    pub steady_available: f64,
+    pub burst_available: u32,
+    pub burst_resets_in: Option<Duration>,
+    pub queued: usize,
+}
+
+/// Token bucket state shared by every request made through a limiter
+#[derive(Debug)]
+struct BucketState {
+    steady_tokens: f64,
+    last_refill: Instant,
+    burst_tokens: u32,
+    burst_window_start: Option<Instant>,
+}
+
+/// Token bucket rate limiter that serves waiting requests in FIFO order
+#[derive(Debug)]
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    state: Mutex<BucketState>,
+    queue: tokio::sync::Mutex<()>,
+    queued: AtomicUsize,
+}
+
+/// Counts a caller as queued until it is dropped, even if its wait is cancelled
+struct QueuedGuard<'a>(&'a AtomicUsize);
+
+impl<'a> QueuedGuard<'a> {
+    fn new(queued: &'a AtomicUsize) -> Self {
+        queued.fetch_add(1, Ordering::SeqCst);
+        Self(queued)
+    }
+}
+
+impl Drop for QueuedGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new(RateLimitConfig::default())
+    }
+}
+
+impl RateLimiter {
+    /// Creates a limiter with full steady and burst buckets
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(BucketState {
+                steady_tokens: Self::steady_capacity(&config),
+                last_refill: Instant::now(),
+                burst_tokens: config.burst_limit,
+                burst_window_start: None,
+            }),
+            queue: tokio::sync::Mutex::new(()),
+            queued: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the limits this limiter enforces
+    pub fn config(&self) -> RateLimitConfig {
+        self.config
+    }
+
+    /// Waits until a request may be sent, then consumes one token
+    ///
+    /// Callers are served in the order they started waiting, so concurrent
+    /// ship tasks share the budget fairly.
+    pub async fn acquire(&self) {
+        let waiting = QueuedGuard::new(&self.queued);
+        let _turn = self.queue.lock().await;
+        drop(waiting);
+
+        loop {
+            let wait = {
+                let mut state = self.state.lock().unwrap();
+                match self.try_take(&mut state, Instant::now()) {
+                    Ok(()) => return,
+                    Err(wait) => wait,
+                }
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Returns the current request budget without consuming anything
+    pub fn budget(&self) -> RateLimitBudget {
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap();
+        self.refill(&mut state, now);
+
+        RateLimitBudget {
+            steady_available: state.steady_tokens,
+            burst_available: state.burst_tokens,
+            burst_resets_in: state
+                .burst_window_start
+                .map(|start| (start + self.config.burst_window).saturating_duration_since(now)),
+            queued: self.queued.load(Ordering::SeqCst),
+        }
+    }
+
+    /// The steady bucket holds one second worth of requests, and at least one
+    fn steady_capacity(config: &RateLimitConfig) -> f64 {
+        config.requests_per_second.max(1.0)
+    }
+
+    /// Tops up the steady bucket and resets the burst pool once its window has passed
+    fn refill(&self, state: &mut BucketState, now: Instant) {
+        let elapsed = now.saturating_duration_since(state.last_refill).as_secs_f64();
+        state.steady_tokens = (state.steady_tokens + elapsed * self.config.requests_per_second)
+            .min(Self::steady_capacity(&self.config));
+        state.last_refill = now;
+
+        if let Some(start) = state.burst_window_start
+            && now >= start + self.config.burst_window
+        {
+            state.burst_tokens = self.config.burst_limit;
+            state.burst_window_start = None;
+        }
+    }
+
+    /// Consumes a token, or returns how long to wait before one is available
+    fn try_take(&self, state: &mut BucketState, now: Instant) -> Result<(), Duration> {
+        self.refill(state, now);
+
+        if state.steady_tokens >= 1.0 {
+            state.steady_tokens -= 1.0;
+            return Ok(());
+        }
+
+        if state.burst_tokens > 0 {
+            state.burst_tokens -= 1;
+            state.burst_window_start.get_or_insert(now);
+            return Ok(());
+        }
+
+        let steady_wait = Duration::from_secs_f64(
+            (1.0 - state.steady_tokens) / self.config.requests_per_second,
+        );
+        let burst_wait = state
+            .burst_window_start
+            .map(|start| (start + self.config.burst_window).saturating_duration_since(now))
+            .unwrap_or(steady_wait);
+
+        Err(steady_wait.min(burst_wait))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn small_config() -> RateLimitConfig {
+        RateLimitConfig {
+            requests_per_second: 2.0,
+            burst_limit: 3,
+            burst_window: Duration::from_secs(10),
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_burst_is_used_after_steady_bucket() {
+        let limiter = RateLimiter::new(small_config());
+
+        for _ in 0..5 {
+            limiter.acquire().await;
+        }
+
+        let budget = limiter.budget();
+        assert!(budget.steady_available < 1.0);
+        assert_eq!(budget.burst_available, 0);
+        assert_eq!(budget.burst_resets_in, Some(Duration::from_secs(10)));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_acquire_waits_for_steady_refill() {
+        let limiter = RateLimiter::new(small_config());
+        let start = Instant::now();
+
+        for _ in 0..6 {
+            limiter.acquire().await;
+        }
+
+        assert_eq!(start.elapsed(), Duration::from_millis(500));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_burst_pool_resets_after_window() {
+        let limiter = RateLimiter::new(small_config());
+
+        for _ in 0..5 {
+            limiter.acquire().await;
+        }
+        tokio::time::advance(Duration::from_secs(10)).await;
+
+        let budget = limiter.budget();
+        assert_eq!(budget.burst_available, 3);
+        assert_eq!(budget.steady_available, 2.0);
+        assert_eq!(budget.burst_resets_in, None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_concurrent_tasks_share_budget() {
+        let limiter = std::sync::Arc::new(RateLimiter::new(small_config()));
+        let start = Instant::now();
+
+        let tasks: Vec<_> = (0..7)
+            .map(|_| {
+                let limiter = limiter.clone();
+                tokio::spawn(async move { limiter.acquire().await })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        assert_eq!(start.elapsed(), Duration::from_secs(1));
+        assert_eq!(limiter.budget().queued, 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_cancelled_wait_leaves_queue() {
+        let limiter = std::sync::Arc::new(RateLimiter::new(small_config()));
+        for _ in 0..5 {
+            limiter.acquire().await;
+        }
+
+        // The first waiter holds the queue while it sleeps for a token
+        let holder = {
+            let limiter = limiter.clone();
+            tokio::spawn(async move { limiter.acquire().await })
+        };
+        tokio::task::yield_now().await;
+
+        let cancelled = tokio::time::timeout(Duration::from_millis(10), limiter.acquire()).await;
+        assert!(cancelled.is_err());
+        assert_eq!(limiter.budget().queued, 0);
+
+        holder.await.unwrap();
+        assert_eq!(limiter.budget().queued, 0);
+    }
+}
@@ -0,0 +1,186 @@
+//! Retry policies for rate-limited and failed API requests
+
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, Utc};
+use reqwest::header::HeaderMap;
+use reqwest::{Method, StatusCode};
+
+/// How a request is retried when the server rejects it with 429 or 5xx
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    pub retry_server_errors: bool,
+}
+
+impl RetryPolicy {
+    /// Retries 429s, 5xx responses and connection failures
+    ///
+    /// Only safe for requests that can be repeated without side effects.
+    pub fn idempotent() -> Self {
+        Self {
+            max_attempts: 5,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            retry_server_errors: true,
+        }
+    }
+
+    /// Retries 429s only
+    ///
+    /// A rate-limited request was rejected before it was processed, so it can be
+    /// resent, but a 5xx on a purchase or navigation may already have taken effect.
+    pub fn non_idempotent() -> Self {
+        Self {
+            retry_server_errors: false,
+            ..Self::idempotent()
+        }
+    }
+
+    /// Sends the request exactly once
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::non_idempotent()
+        }
+    }
+
+    /// Default policy for an HTTP method
+    pub fn for_method(method: &Method) -> Self {
+        if *method == Method::GET || *method == Method::PATCH {
+            Self::idempotent()
+        } else {
+            Self::non_idempotent()
+        }
+    }
+
+    /// Whether a response with this status should be retried under this policy
+    pub fn should_retry(&self, status: StatusCode) -> bool {
+        status == StatusCode::TOO_MANY_REQUESTS || (self.retry_server_errors && status.is_server_error())
+    }
+
+    /// Exponential backoff for the given attempt (starting at 1), capped at `max_delay`
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.base_delay.saturating_mul(factor).min(self.max_delay)
+    }
+
+    /// Delay before the next attempt: the server's hint if any, else backoff, plus jitter
+    pub fn delay(&self, attempt: u32, headers: &HeaderMap) -> Duration {
+        let delay = server_retry_hint(headers)
+            .map(|hint| hint.min(self.max_delay))
+            .unwrap_or_else(|| self.backoff(attempt));
+        delay + jitter(delay)
+    }
+}
+
+/// Error returned when a request was still rejected after every allowed attempt
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetriesExhausted {
+    pub method: String,
+    pub path: String,
+    pub attempts: u32,
+    pub status: Option<u16>,
+    pub body: String,
+}
+
+impl fmt::Display for RetriesExhausted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.status {
+            Some(status) => write!(
+                f,
+                "{} {} still failing with status {} after {} attempts: {}",
+                self.method, self.path, status, self.attempts, self.body
+            ),
+            None => write!(
+                f,
+                "{} {} still failing after {} attempts: {}",
+                self.method, self.path, self.attempts, self.body
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RetriesExhausted {}
+
+/// Reads how long the server asked us to wait from `Retry-After` or `x-ratelimit-reset`
+pub fn server_retry_hint(headers: &HeaderMap) -> Option<Duration> {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok()).map(str::trim);
+
+    if let Some(seconds) = header("retry-after").and_then(|v| v.parse::<f64>().ok())
+        && seconds >= 0.0
+    {
+        return Some(Duration::from_secs_f64(seconds));
+    }
+
+    let reset = header("x-ratelimit-reset")?;
+    let reset = DateTime::parse_from_rfc3339(reset).ok()?.with_timezone(&Utc);
+    Some((reset - Utc::now()).to_std().unwrap_or_default())
+}
+
+/// Random extra delay of up to a quarter of `delay`, so concurrent tasks do not retry in lockstep
+fn jitter(delay: Duration) -> Duration {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .subsec_nanos() as u64;
+    let mut seed = nanos ^ 0x9E37_79B9_7F4A_7C15;
+    seed ^= seed << 13;
+    seed ^= seed >> 7;
+    seed ^= seed << 17;
+
+    let fraction = (seed % 1000) as f64 / 1000.0;
+    delay.mul_f64(0.25 * fraction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_default_policy_per_method() {
+        assert!(RetryPolicy::for_method(&Method::GET).retry_server_errors);
+        assert!(!RetryPolicy::for_method(&Method::POST).retry_server_errors);
+    }
+
+    #[test]
+    fn test_non_idempotent_only_retries_rate_limits() {
+        let policy = RetryPolicy::non_idempotent();
+        assert!(policy.should_retry(StatusCode::TOO_MANY_REQUESTS));
+        assert!(!policy.should_retry(StatusCode::BAD_GATEWAY));
+        assert!(!policy.should_retry(StatusCode::BAD_REQUEST));
+    }
+
+    #[test]
+    fn test_backoff_doubles_and_caps() {
+        let policy = RetryPolicy::idempotent();
+        assert_eq!(policy.backoff(1), Duration::from_millis(500));
+        assert_eq!(policy.backoff(3), Duration::from_secs(2));
+        assert_eq!(policy.backoff(20), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_retry_after_header_takes_precedence() {
+        let mut headers = HeaderMap::new();
+        headers.insert("retry-after", HeaderValue::from_static("1.5"));
+
+        let delay = RetryPolicy::idempotent().delay(1, &headers);
+        assert!(delay >= Duration::from_millis(1500));
+        assert!(delay <= Duration::from_millis(1875));
+    }
+
+    #[test]
+    fn test_ratelimit_reset_header_is_parsed() {
+        let reset = (Utc::now() + chrono::Duration::seconds(10)).to_rfc3339();
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-reset", HeaderValue::from_str(&reset).unwrap());
+
+        let hint = server_retry_hint(&headers).unwrap();
+        assert!(hint > Duration::from_secs(8));
+        assert!(hint <= Duration::from_secs(10));
+    }
+}
@@ -0,0 +1,415 @@
+//! Status storage shared between concurrent ship tasks
+//!
+//! Wraps `StatusStorage` in a `RwLock` behind a cloneable handle. Every change
+//! to a ship's status is broadcast to subscribers, and background tasks can
+//! clear expired entries and save snapshots periodically. Cached API data can
+//! be served stale while a background task refreshes it.
+
+use std::collections::HashSet;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use tokio::sync::{Mutex as AsyncMutex, RwLock, RwLockReadGuard, broadcast};
+use tokio::task::JoinHandle;
+
+use crate::error::SpaceTradersError;
+use crate::markets::MarketObservation;
+use crate::models::{RefuelResult, Ship, ShipNavUpdate, Survey, TradeResult};
+use crate::status_storage::{CacheCategory, Freshness, ShipStatus, StatusStorage};
+
+/// Number of status changes a slow subscriber can fall behind before it misses some
+const CHANGE_CHANNEL_CAPACITY: usize = 256;
+
+/// Cloneable, thread-safe handle to a `StatusStorage`
+#[derive(Debug, Clone)]
+pub struct SharedStatusStorage {
+    inner: Arc<RwLock<StatusStorage>>,
+    changes: broadcast::Sender<ShipStatus>,
+    refreshing: Arc<Mutex<HashSet<(CacheCategory, String)>>>,
+    /// Held while a snapshot is written, so saves land in the order they were taken
+    saving: Arc<AsyncMutex<()>>,
+}
+
+impl Default for SharedStatusStorage {
+    fn default() -> Self {
+        Self::new(StatusStorage::new())
+    }
+}
+
+impl SharedStatusStorage {
+    /// Shares an existing storage, e.g. one loaded from disk
+    pub fn new(storage: StatusStorage) -> Self {
+        let (changes, _) = broadcast::channel(CHANGE_CHANNEL_CAPACITY);
+        Self {
+            inner: Arc::new(RwLock::new(storage)),
+            changes,
+            refreshing: Arc::default(),
+            saving: Arc::default(),
+        }
+    }
+
+    /// Receives every ship status written from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<ShipStatus> {
+        self.changes.subscribe()
+    }
+
+    /// Locks the storage for reading; keep the guard short-lived
+    pub async fn read(&self) -> RwLockReadGuard<'_, StatusStorage> {
+        self.inner.read().await
+    }
+
+    /// Updates or creates a ship status
+    pub async fn update_status(&self, status: ShipStatus) {
+        let ship_symbol = status.ship_symbol.clone();
+        let mut storage = self.inner.write().await;
+        storage.update_status(status);
+        self.notify(&storage, &ship_symbol);
+    }
+
+    /// Updates a ship's status from the ship object returned by the API
+    pub async fn update_from_ship(&self, ship: &Ship) {
+        let mut storage = self.inner.write().await;
+        storage.update_from_ship(ship);
+        self.notify(&storage, &ship.symbol);
+    }
+
+    /// Records a ship's nav state after an orbit, dock, navigation or warp
+    pub async fn record_navigation(&self, ship_symbol: &str, update: &ShipNavUpdate) {
+        let mut storage = self.inner.write().await;
+        storage.record_navigation(ship_symbol, update);
+        self.notify(&storage, ship_symbol);
+    }
+
+    /// Records a purchase or sale and the ship's resulting cargo
+    pub async fn record_trade(&self, result: &TradeResult) {
+        let mut storage = self.inner.write().await;
+        storage.record_trade(result);
+        self.notify(&storage, &result.transaction.ship_symbol);
+    }
+
+    /// Records a refuel and the ship's resulting fuel
+    pub async fn record_refuel(&self, result: &RefuelResult) {
+        let mut storage = self.inner.write().await;
+        storage.record_refuel(result);
+        self.notify(&storage, &result.transaction.ship_symbol);
+    }
+
+    /// Appends a market observation's trade goods to the price history
+    pub async fn record_prices(&self, observation: &MarketObservation) {
+        if let Some(goods) = &observation.market.trade_goods {
+            let mut storage = self.inner.write().await;
+            storage.record_prices(&observation.market.symbol, observation.observed_at, goods);
+        }
+    }
+
+    /// Gets a ship's current status if it is still valid
+    pub async fn get_status(&self, ship_symbol: &str) -> Option<ShipStatus> {
+        self.inner.read().await.get_status(ship_symbol)
+    }
+
+    /// Adds a survey, replacing any stored survey with the same signature
+    pub async fn update_survey(&self, survey: Survey) {
+        self.inner.write().await.update_survey(survey);
+    }
+
+    /// Removes a survey, e.g. once it is exhausted
+    pub async fn remove_survey(&self, signature: &str) {
+        self.inner.write().await.remove_survey(signature);
+    }
+
+    /// Caches API data under a category and key
+    pub async fn cache_put<T: Serialize>(
+        &self,
+        category: CacheCategory,
+        key: &str,
+        value: &T
+    ) -> Result<(), SpaceTradersError> {
+        self.inner.write().await.cache_put(category, key, value)
+    }
+
+    /// Looks up cached API data, reporting whether it is still fresh
+    pub async fn cache_get<T: DeserializeOwned>(&self, category: CacheCategory, key: &str) -> Freshness<T> {
+        self.inner.read().await.cache_get(category, key)
+    }
+
+    /// Returns cached data for a key, fetching it with `fetch` when needed
+    ///
+    /// Fresh data is returned as is. Stale data is returned immediately while
+    /// `fetch` runs in the background if the policy allows stale-while-revalidate;
+    /// otherwise the caller waits for `fetch` and the result is cached.
+    pub async fn cached<T, F, Fut>(
+        &self,
+        category: CacheCategory,
+        key: &str,
+        fetch: F
+    ) -> Result<T, SpaceTradersError>
+    where
+        T: Serialize + DeserializeOwned + Send + 'static,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, SpaceTradersError>> + Send + 'static,
+    {
+        let (cached, revalidate) = {
+            let storage = self.inner.read().await;
+            (storage.cache_get::<T>(category, key), storage.policy().stale_while_revalidate)
+        };
+
+        match cached {
+            Freshness::Fresh(value) => Ok(value),
+            Freshness::Stale(value) if revalidate => {
+                self.spawn_refresh(category, key, fetch());
+                Ok(value)
+            }
+            Freshness::Stale(_) | Freshness::Missing => {
+                let value = fetch().await?;
+                self.inner.write().await.cache_put(category, key, &value)?;
+                Ok(value)
+            }
+        }
+    }
+
+    /// Refreshes a cache entry in the background, at most once per key at a time
+    fn spawn_refresh<T, Fut>(&self, category: CacheCategory, key: &str, fetch: Fut)
+    where
+        T: Serialize + Send + 'static,
+        Fut: Future<Output = Result<T, SpaceTradersError>> + Send + 'static,
+    {
+        let id = (category, key.to_string());
+        if !self.refreshing.lock().unwrap_or_else(|e| e.into_inner()).insert(id.clone()) {
+            return;
+        }
+
+        let storage = self.clone();
+        tokio::spawn(async move {
+            match fetch.await {
+                Ok(value) => {
+                    if let Err(e) = storage.inner.write().await.cache_put(id.0, &id.1, &value) {
+                        eprintln!("Failed to cache {:?} {}: {}", id.0, id.1, e);
+                    }
+                }
+                Err(e) => eprintln!("Failed to refresh {:?} {}: {}", id.0, id.1, e),
+            }
+            storage.refreshing.lock().unwrap_or_else(|e| e.into_inner()).remove(&id);
+        });
+    }
+
+    /// Clears all expired statuses, surveys and scans
+    pub async fn clear_expired(&self) {
+        self.inner.write().await.clear_expired();
+    }
+
+    /// Writes a snapshot of the storage to `path`
+    ///
+    /// Saves from concurrent tasks run one at a time.
+    pub async fn save(&self, path: impl AsRef<Path>) -> Result<(), SpaceTradersError> {
+        let _saving = self.saving.lock().await;
+        self.inner.read().await.save(path)
+    }
+
+    /// Spawns a task that clears expired entries every `period`
+    ///
+    /// The task stops on its own once every handle to the storage is dropped.
+    pub fn spawn_expiry_task(&self, period: Duration) -> JoinHandle<()> {
+        let storage: Weak<RwLock<StatusStorage>> = Arc::downgrade(&self.inner);
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            interval.tick().await;
+
+            loop {
+                interval.tick().await;
+                let Some(storage) = storage.upgrade() else {
+                    break;
+                };
+                storage.write().await.clear_expired();
+            }
+        })
+    }
+
+    /// Spawns a task that writes a snapshot to `path` every `period`
+    ///
+    /// This persists what mining, trading and surveys record between explicit
+    /// saves. A failed save is reported and retried on the next tick. The task
+    /// stops on its own once every handle to the storage is dropped.
+    pub fn spawn_save_task(&self, path: impl Into<PathBuf>, period: Duration) -> JoinHandle<()> {
+        let storage: Weak<RwLock<StatusStorage>> = Arc::downgrade(&self.inner);
+        let saving = self.saving.clone();
+        let path = path.into();
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            interval.tick().await;
+
+            loop {
+                interval.tick().await;
+                let Some(storage) = storage.upgrade() else {
+                    break;
+                };
+                let _saving = saving.lock().await;
+                if let Err(e) = storage.read().await.save(&path) {
+                    println!("Could not save status storage to {}: {}", path.display(), e);
+                }
+            }
+        })
+    }
+
+    /// Broadcasts a ship's new status; nobody listening is not an error
+    fn notify(&self, storage: &StatusStorage, ship_symbol: &str) {
+        if let Some(status) = storage.get_status(ship_symbol) {
+            let _ = self.changes.send(status);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{SurveyDeposit, SurveySize};
+    use crate::status_storage::{CachePolicy, ShipStatusType};
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn status(ship_symbol: &str) -> ShipStatus {
+        ShipStatus {
+            ship_symbol: ship_symbol.to_string(),
+            status_type: ShipStatusType::Idle,
+            location: "X1-DF55-A1".to_string(),
+            cargo: vec![],
+            fuel: 100,
+            last_updated: 0,
+            expires_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_tasks_update_and_notify() {
+        let storage = SharedStatusStorage::default();
+        let mut changes = storage.subscribe();
+
+        let tasks: Vec<_> = (0..4)
+            .map(|i| {
+                let storage = storage.clone();
+                tokio::spawn(async move { storage.update_status(status(&format!("SHIP-{}", i))).await })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        assert_eq!(storage.read().await.len(), 4);
+
+        let mut notified: Vec<String> = Vec::new();
+        for _ in 0..4 {
+            notified.push(changes.recv().await.unwrap().ship_symbol);
+        }
+        notified.sort();
+        assert_eq!(notified, ["SHIP-0", "SHIP-1", "SHIP-2", "SHIP-3"]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_expiry_task_clears_expired_surveys() {
+        let storage = SharedStatusStorage::default();
+        storage
+            .update_survey(Survey {
+                signature: "STALE".to_string(),
+                symbol: "X1-DF55-A1".to_string(),
+                deposits: vec![SurveyDeposit { symbol: "IRON_ORE".to_string() }],
+                expiration: "2020-01-01T00:00:00.000Z".to_string(),
+                size: SurveySize::Small,
+            })
+            .await;
+
+        let task = storage.spawn_expiry_task(Duration::from_secs(30));
+        tokio::time::sleep(Duration::from_secs(31)).await;
+
+        assert!(storage.read().await.get_survey("STALE").is_none());
+
+        drop(storage);
+        tokio::time::sleep(Duration::from_secs(30)).await;
+        assert!(task.is_finished());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_save_task_persists_later_changes() {
+        let path = std::env::temp_dir().join(format!("shared_storage_{}_autosave.json", std::process::id()));
+        let storage = SharedStatusStorage::default();
+        let task = storage.spawn_save_task(&path, Duration::from_secs(30));
+
+        storage.update_status(status("SHIP-1")).await;
+        tokio::time::sleep(Duration::from_secs(31)).await;
+
+        let loaded = StatusStorage::load(&path, CachePolicy::default()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(loaded.get_status("SHIP-1").is_some());
+
+        drop(storage);
+        tokio::time::sleep(Duration::from_secs(30)).await;
+        assert!(task.is_finished());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_saves_leave_a_valid_snapshot() {
+        let path = std::env::temp_dir().join(format!("shared_storage_{}_saves.json", std::process::id()));
+        let storage = SharedStatusStorage::default();
+        storage.update_status(status("SHIP-1")).await;
+
+        let tasks: Vec<_> = (0..8)
+            .map(|_| {
+                let storage = storage.clone();
+                let path = path.clone();
+                tokio::spawn(async move { storage.save(&path).await })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap().unwrap();
+        }
+
+        let loaded = StatusStorage::load(&path, CachePolicy::default()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(loaded.get_status("SHIP-1").is_some());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_stale_entries_served_while_refreshing() {
+        let storage = SharedStatusStorage::new(StatusStorage::with_policy(CachePolicy {
+            markets_ttl: Some(0),
+            ..CachePolicy::default()
+        }));
+        let fetches = Arc::new(AtomicU32::new(0));
+        let fetch = || {
+            let fetches = fetches.clone();
+            async move { Ok(fetches.fetch_add(1, Ordering::SeqCst) + 1) }
+        };
+
+        let first: u32 = storage.cached(CacheCategory::Markets, "X1-DF55-A1", fetch).await.unwrap();
+        assert_eq!(first, 1);
+
+        // Every entry is stale at once, so this returns the old value and refreshes it
+        let second: u32 = storage.cached(CacheCategory::Markets, "X1-DF55-A1", fetch).await.unwrap();
+        assert_eq!(second, 1);
+
+        // The paused clock only advances once the refresh task has nothing left to do
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        assert!(storage.refreshing.lock().unwrap().is_empty());
+        assert_eq!(fetches.load(Ordering::SeqCst), 2);
+        let third: u32 = storage.cached(CacheCategory::Markets, "X1-DF55-A1", fetch).await.unwrap();
+        assert_eq!(third, 2);
+    }
+
+    #[tokio::test]
+    async fn test_stale_entries_refetched_without_revalidate() {
+        let storage = SharedStatusStorage::new(StatusStorage::with_policy(CachePolicy {
+            markets_ttl: Some(0),
+            stale_while_revalidate: false,
+            ..CachePolicy::default()
+        }));
+
+        let first: u32 = storage.cached(CacheCategory::Markets, "X1-DF55-A1", || async { Ok(1) }).await.unwrap();
+        let second: u32 = storage.cached(CacheCategory::Markets, "X1-DF55-A1", || async { Ok(2) }).await.unwrap();
+
+        assert_eq!((first, second), (1, 2));
+    }
+}
@@ -0,0 +1,86 @@
+//! Fleet listing and ship details
+
+use crate::client::ApiClient;
+use crate::error::SpaceTradersError;
+use crate::models::{ApiResponse, Ship};
+use crate::shared_storage::SharedStatusStorage;
+
+/// Lists every ship owned by the agent, across all pages
+pub async fn list_ships(
+    client: &ApiClient
+) -> Result<Vec<Ship>, SpaceTradersError> {
+    client.get_all("/my/ships").await
+}
+
+/// Gets a single ship with its nav, crew, components, cargo, fuel and cooldown
+pub async fn get_ship(
+    client: &ApiClient,
+    ship_symbol: &str
+) -> Result<Ship, SpaceTradersError> {
+    let response: ApiResponse<Ship> = client.get(&format!("/my/ships/{}", ship_symbol)).await?;
+    Ok(response.data)
+}
+
+/// Lists the fleet and records every ship's status in the storage
+pub async fn refresh_fleet_status(
+    client: &ApiClient,
+    storage: &SharedStatusStorage
+) -> Result<Vec<Ship>, SpaceTradersError> {
+    let ships = list_ships(client).await?;
+
+    for ship in &ships {
+        storage.update_from_ship(ship).await;
+    }
+
+    Ok(ships)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_server::{MockResponse, MockServer, ship_json};
+    use crate::models::ShipNavStatus;
+    use crate::status_storage::ShipStatusType;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_get_ship_parses_full_model() {
+        let server = MockServer::start().await;
+        server.route("GET", "/my/ships/SHIP-1", vec![MockResponse::json(200, json!({"data": ship_json("SHIP-1")}))]);
+
+        let client = ApiClient::with_base_url(server.base_url(), Some("test_token"));
+        let ship = get_ship(&client, "SHIP-1").await.unwrap();
+
+        assert_eq!(ship.nav.status, ShipNavStatus::Docked);
+        assert_eq!(ship.frame.fuel_capacity, 400);
+        assert_eq!(ship.engine.speed, 10);
+        assert_eq!(ship.modules[0].capacity, Some(15));
+        assert_eq!(ship.mounts[0].strength, Some(10));
+        assert_eq!(ship.cargo.inventory[0].symbol, "IRON_ORE");
+        assert_eq!(ship.cooldown.remaining_seconds, 0);
+    }
+
+    #[tokio::test]
+    async fn test_refresh_fleet_status_stores_every_ship() {
+        let first: Vec<_> = (0..20).map(|i| ship_json(&format!("SHIP-{}", i))).collect();
+        let server = MockServer::start().await;
+        server.route("GET", "/my/ships?page=1&limit=20", vec![MockResponse::json(200, json!({
+            "data": first,
+            "meta": {"total": 21, "page": 1, "limit": 20}
+        }))]);
+        server.route("GET", "/my/ships?page=2&limit=20", vec![MockResponse::json(200, json!({
+            "data": [ship_json("SHIP-20")],
+            "meta": {"total": 21, "page": 2, "limit": 20}
+        }))]);
+
+        let client = ApiClient::with_base_url(server.base_url(), Some("test_token"));
+        let storage = SharedStatusStorage::default();
+        let ships = refresh_fleet_status(&client, &storage).await.unwrap();
+
+        assert_eq!(ships.len(), 21);
+        assert_eq!(storage.read().await.len(), 21);
+        let status = storage.get_status("SHIP-20").await.unwrap();
+        assert_eq!(status.status_type, ShipStatusType::Idle);
+        assert_eq!(status.location, "X1-DF55-20250Z");
+    }
+}
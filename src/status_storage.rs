@@ -1,9 +1,21 @@
 //! Status storage system for tracking ship activities and reducing API calls
 
 use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{SystemTime, UNIX_EPOCH};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 
+use crate::error::SpaceTradersError;
+use crate::models::{
+    MarketTradeGood, MarketTransaction, RefuelResult, Ship, ShipCargo, ShipNavStatus, ShipNavUpdate, TradeResult,
+    unix_timestamp,
+};
+use crate::price_history::PriceHistory;
+
 /// Represents the current status of a ship
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ShipStatus {
@@ -34,23 +46,36 @@ pub struct CargoItem {
     pub units: i32,
 }
 
-/// Represents a survey of an asteroid
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Survey {
-    pub symbol: String,
-    pub deposits: Vec<String>,
-    pub expiration: u64, // Unix timestamp when survey expires
-    pub size: SurveySize,
-}
+impl From<&Ship> for ShipStatus {
+    /// Builds a status from a ship returned by the API
+    ///
+    /// Ships in transit stay `Traveling` until they arrive; ships cooling down
+    /// after an extraction are `Mining` until the cooldown ends. Anything else
+    /// is `Idle` and expires after the storage's max age.
+    fn from(ship: &Ship) -> Self {
+        let (status_type, expires_at) = if ship.nav.status == ShipNavStatus::InTransit {
+            (ShipStatusType::Traveling, unix_timestamp(&ship.nav.route.arrival))
+        } else if ship.cooldown.remaining_seconds > 0 {
+            let expires_at = ship.cooldown.expiration.as_deref().and_then(unix_timestamp);
+            (ShipStatusType::Mining, expires_at)
+        } else {
+            (ShipStatusType::Idle, None)
+        };
 
-/// Size of a survey
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum SurveySize {
-    Small,
-    Medium,
-    Large,
+        Self {
+            ship_symbol: ship.symbol.clone(),
+            status_type,
+            location: ship.nav.waypoint_symbol.clone(),
+            cargo: cargo_items(&ship.cargo),
+            fuel: ship.fuel.current,
+            last_updated: 0,
+            expires_at,
+        }
+    }
 }
 
+pub use crate::models::{Survey, SurveyDeposit, SurveySize};
+
 /// Represents a scan of an asteroid
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Scan {
@@ -66,36 +91,264 @@ pub struct ScanMaterial {
     pub units: i32,
 }
 
+/// Kinds of data the storage caches, each with its own time to live
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CacheCategory {
+    Ships,
+    Waypoints,
+    Markets,
+    Shipyards,
+    Systems,
+}
+
+/// How long each category of data stays fresh, in seconds; `None` never expires
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CachePolicy {
+    pub ships_ttl: Option<u64>,
+    pub waypoints_ttl: Option<u64>,
+    pub markets_ttl: Option<u64>,
+    pub shipyards_ttl: Option<u64>,
+    pub systems_ttl: Option<u64>,
+    /// How long past its TTL an entry is kept and may still be served as stale
+    pub max_stale_seconds: u64,
+    /// Serve stale entries immediately and refresh them in the background
+    pub stale_while_revalidate: bool,
+}
+
+impl Default for CachePolicy {
+    fn default() -> Self {
+        Self {
+            ships_ttl: Some(300),
+            waypoints_ttl: Some(3600),
+            markets_ttl: Some(120),
+            shipyards_ttl: Some(900),
+            systems_ttl: None, // Systems only change when the server resets
+            max_stale_seconds: 3600,
+            stale_while_revalidate: true,
+        }
+    }
+}
+
+impl CachePolicy {
+    /// Time to live of a category
+    pub fn ttl(&self, category: CacheCategory) -> Option<u64> {
+        match category {
+            CacheCategory::Ships => self.ships_ttl,
+            CacheCategory::Waypoints => self.waypoints_ttl,
+            CacheCategory::Markets => self.markets_ttl,
+            CacheCategory::Shipyards => self.shipyards_ttl,
+            CacheCategory::Systems => self.systems_ttl,
+        }
+    }
+}
+
+/// Result of a cache lookup
+#[derive(Debug, Clone, PartialEq)]
+pub enum Freshness<T> {
+    /// Within its TTL
+    Fresh(T),
+    /// Past its TTL but within the stale window; should be refreshed
+    Stale(T),
+    /// Never cached, expired beyond the stale window, or unreadable
+    Missing,
+}
+
+/// Cached API data together with when it was fetched
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    value: serde_json::Value,
+    fetched_at: u64,
+}
+
+/// Version of the on-disk snapshot format, bumped whenever its layout changes
+pub const SNAPSHOT_VERSION: u32 = 4;
+
+/// Number of market transactions kept; older ones are dropped first
+pub const MAX_TRANSACTIONS: usize = 1000;
+
+/// On-disk snapshot of a status storage
+///
+/// Older snapshots lack the cache, credits, transactions or price history and are still accepted.
+#[derive(Debug, Serialize, Deserialize)]
+struct StorageSnapshot {
+    version: u32,
+    statuses: HashMap<String, ShipStatus>,
+    surveys: HashMap<String, Vec<Survey>>,
+    scans: HashMap<String, Scan>,
+    #[serde(default)]
+    cache: HashMap<CacheCategory, HashMap<String, CacheEntry>>,
+    #[serde(default)]
+    credits: Option<i64>,
+    #[serde(default)]
+    transactions: Vec<MarketTransaction>,
+    #[serde(default)]
+    price_history: PriceHistory,
+}
+
 /// Main status storage system
 #[derive(Debug)]
 pub struct StatusStorage {
     statuses: HashMap<String, ShipStatus>,
-    surveys: HashMap<String, Survey>, // Keyed by waypoint symbol
+    surveys: HashMap<String, Vec<Survey>>, // Keyed by waypoint symbol
     scans: HashMap<String, Scan>,     // Keyed by waypoint symbol
-    max_age_seconds: u64,
+    cache: HashMap<CacheCategory, HashMap<String, CacheEntry>>,
+    credits: Option<i64>,
+    transactions: Vec<MarketTransaction>, // Oldest first
+    price_history: PriceHistory,
+    policy: CachePolicy,
+}
+
+impl Default for StatusStorage {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl StatusStorage {
-    /// Creates a new status storage system with default max age of 300 seconds (5 minutes)
+    /// Creates a new status storage system with the default cache policy
     pub fn new() -> Self {
-        Self {
-            statuses: HashMap::new(),
-            surveys: HashMap::new(),
-            scans: HashMap::new(),
-            max_age_seconds: 300, // 5 minutes
-        }
+        Self::with_policy(CachePolicy::default())
     }
 
-    /// Creates a new status storage system with custom max age
+    /// Creates a new status storage system with custom max age for ship statuses
     pub fn with_max_age(max_age_seconds: u64) -> Self {
+        Self::with_policy(CachePolicy {
+            ships_ttl: Some(max_age_seconds),
+            ..CachePolicy::default()
+        })
+    }
+
+    /// Creates a new status storage system with custom TTLs per category
+    pub fn with_policy(policy: CachePolicy) -> Self {
         Self {
             statuses: HashMap::new(),
             surveys: HashMap::new(),
             scans: HashMap::new(),
-            max_age_seconds,
+            cache: HashMap::new(),
+            credits: None,
+            transactions: Vec::new(),
+            price_history: PriceHistory::new(),
+            policy,
         }
     }
 
+    /// Returns the cache policy
+    pub fn policy(&self) -> CachePolicy {
+        self.policy
+    }
+
+    /// Replaces the cache policy
+    pub fn set_policy(&mut self, policy: CachePolicy) {
+        self.policy = policy;
+    }
+
+    /// Caches API data under a category and key, e.g. a market by waypoint symbol
+    pub fn cache_put<T: Serialize>(
+        &mut self,
+        category: CacheCategory,
+        key: &str,
+        value: &T
+    ) -> Result<(), SpaceTradersError> {
+        let entry = CacheEntry {
+            value: serde_json::to_value(value)?,
+            fetched_at: unix_now(),
+        };
+        self.cache.entry(category).or_default().insert(key.to_string(), entry);
+        Ok(())
+    }
+
+    /// Looks up cached API data, reporting whether it is still fresh
+    pub fn cache_get<T: DeserializeOwned>(&self, category: CacheCategory, key: &str) -> Freshness<T> {
+        let Some(entry) = self.cache.get(&category).and_then(|entries| entries.get(key)) else {
+            return Freshness::Missing;
+        };
+        let Ok(value) = serde_json::from_value(entry.value.clone()) else {
+            return Freshness::Missing;
+        };
+
+        let Some(ttl) = self.policy.ttl(category) else {
+            return Freshness::Fresh(value);
+        };
+        let age = unix_now().saturating_sub(entry.fetched_at);
+        if age < ttl {
+            Freshness::Fresh(value)
+        } else if age < ttl + self.policy.max_stale_seconds {
+            Freshness::Stale(value)
+        } else {
+            Freshness::Missing
+        }
+    }
+
+    /// Unix time cached data was fetched
+    pub fn cache_fetched_at(&self, category: CacheCategory, key: &str) -> Option<u64> {
+        self.cache.get(&category)?.get(key).map(|entry| entry.fetched_at)
+    }
+
+    /// Removes cached API data
+    pub fn cache_remove(&mut self, category: CacheCategory, key: &str) {
+        if let Some(entries) = self.cache.get_mut(&category) {
+            entries.remove(key);
+        }
+    }
+
+    /// Writes a snapshot of the storage to `path`
+    ///
+    /// The snapshot goes to a temporary file next to `path` that is flushed to
+    /// disk and then renamed over it, so a crash mid-write leaves the previous
+    /// snapshot intact.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), SpaceTradersError> {
+        let snapshot = StorageSnapshot {
+            version: SNAPSHOT_VERSION,
+            statuses: self.statuses.clone(),
+            surveys: self.surveys.clone(),
+            scans: self.scans.clone(),
+            cache: self.cache.clone(),
+            credits: self.credits,
+            transactions: self.transactions.clone(),
+            price_history: self.price_history.clone(),
+        };
+        write_atomically(path.as_ref(), &serde_json::to_vec_pretty(&snapshot)?)?;
+
+        Ok(())
+    }
+
+    /// Loads a snapshot written by `save`, dropping entries that expired in the meantime
+    ///
+    /// Cached data is expired by `policy`, which the loaded storage keeps. A
+    /// missing file yields an empty storage, so a first run starts fresh.
+    pub fn load(path: impl AsRef<Path>, policy: CachePolicy) -> Result<Self, SpaceTradersError> {
+        let json = match fs::read(path.as_ref()) {
+            Ok(json) => json,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::with_policy(policy)),
+            Err(e) => return Err(e.into()),
+        };
+
+        let snapshot: serde_json::Value = serde_json::from_slice(&json)?;
+        let version = snapshot.get("version").and_then(|v| v.as_u64()).unwrap_or(0);
+        if version == 0 || version > SNAPSHOT_VERSION as u64 {
+            let message = format!(
+                "status storage snapshot has version {}, expected at most {}",
+                version, SNAPSHOT_VERSION
+            );
+            return Err(io::Error::new(io::ErrorKind::InvalidData, message).into());
+        }
+        let snapshot: StorageSnapshot = serde_json::from_value(snapshot)?;
+
+        let mut storage = Self {
+            statuses: snapshot.statuses,
+            surveys: snapshot.surveys,
+            scans: snapshot.scans,
+            cache: snapshot.cache,
+            credits: snapshot.credits,
+            transactions: snapshot.transactions,
+            price_history: snapshot.price_history,
+            policy,
+        };
+        storage.clear_expired();
+
+        Ok(storage)
+    }
+
     /// Updates or creates a ship status
     pub fn update_status(&mut self, mut status: ShipStatus) {
         let now = SystemTime::now()
@@ -105,7 +358,7 @@ impl StatusStorage {
         
         // Set expiration time if not already set
         if status.expires_at.is_none() {
-            status.expires_at = Some(now + self.max_age_seconds);
+            status.expires_at = self.policy.ships_ttl.map(|ttl| now + ttl);
         }
         
         // Update last_updated timestamp
@@ -114,6 +367,93 @@ impl StatusStorage {
         self.statuses.insert(status.ship_symbol.clone(), status);
     }
 
+    /// Updates a ship's status from the ship object returned by the API
+    pub fn update_from_ship(&mut self, ship: &Ship) {
+        self.update_status(ShipStatus::from(ship));
+    }
+
+    /// Records a ship's nav state after an orbit, dock, navigation or warp
+    ///
+    /// Ships in transit are `Traveling` until their arrival time. Nav updates
+    /// do not report cargo, so it is carried over from the previous status.
+    pub fn record_navigation(&mut self, ship_symbol: &str, update: &ShipNavUpdate) {
+        let previous = self.statuses.get(ship_symbol);
+        let cargo = previous.map(|status| status.cargo.clone()).unwrap_or_default();
+        let fuel = update
+            .fuel
+            .as_ref()
+            .map(|fuel| fuel.current)
+            .or(previous.map(|status| status.fuel))
+            .unwrap_or(0);
+
+        let (status_type, expires_at) = if update.nav.status == ShipNavStatus::InTransit {
+            (ShipStatusType::Traveling, update.arrival())
+        } else {
+            (ShipStatusType::Idle, None)
+        };
+
+        self.update_status(ShipStatus {
+            ship_symbol: ship_symbol.to_string(),
+            status_type,
+            location: update.nav.waypoint_symbol.clone(),
+            cargo,
+            fuel,
+            last_updated: 0,
+            expires_at,
+        });
+    }
+
+    /// Records a purchase or sale: the ship's new cargo, the agent's credits and the transaction
+    pub fn record_trade(&mut self, result: &TradeResult) {
+        let transaction = &result.transaction;
+        if let Some(status) = self.statuses.get_mut(&transaction.ship_symbol) {
+            status.cargo = cargo_items(&result.cargo);
+            status.last_updated = unix_now();
+        }
+        self.credits = Some(result.agent.credits);
+        self.push_transaction(transaction.clone());
+    }
+
+    /// Records a refuel: the ship's new fuel, the agent's credits and the transaction
+    pub fn record_refuel(&mut self, result: &RefuelResult) {
+        let transaction = &result.transaction;
+        if let Some(status) = self.statuses.get_mut(&transaction.ship_symbol) {
+            status.fuel = result.fuel.current;
+            status.last_updated = unix_now();
+        }
+        self.credits = Some(result.agent.credits);
+        self.push_transaction(transaction.clone());
+    }
+
+    /// Appends a transaction, dropping the oldest beyond `MAX_TRANSACTIONS`
+    fn push_transaction(&mut self, transaction: MarketTransaction) {
+        self.transactions.push(transaction);
+        if self.transactions.len() > MAX_TRANSACTIONS {
+            let excess = self.transactions.len() - MAX_TRANSACTIONS;
+            self.transactions.drain(..excess);
+        }
+    }
+
+    /// Agent credits after the last recorded trade
+    pub fn credits(&self) -> Option<i64> {
+        self.credits
+    }
+
+    /// Recorded market transactions, oldest first
+    pub fn transactions(&self) -> &[MarketTransaction] {
+        &self.transactions
+    }
+
+    /// Appends a market's trade goods, observed at `observed_at`, to the price history
+    pub fn record_prices(&mut self, waypoint_symbol: &str, observed_at: u64, goods: &[MarketTradeGood]) {
+        self.price_history.record(waypoint_symbol, observed_at, goods);
+    }
+
+    /// Prices observed at every market so far
+    pub fn price_history(&self) -> &PriceHistory {
+        &self.price_history
+    }
+
     /// Gets a ship's current status, checking if it's still valid
     pub fn get_status(&self, ship_symbol: &str) -> Option<ShipStatus> {
         if let Some(status) = self.statuses.get(ship_symbol) {
@@ -193,15 +533,24 @@ impl StatusStorage {
             }
         });
         
-        // Clear expired surveys
-        self.surveys.retain(|_symbol, survey| {
-            now < survey.expiration
+        // Clear expired surveys, and waypoints left without any
+        self.surveys.retain(|_symbol, surveys| {
+            surveys.retain(|survey| survey_is_valid(survey, now));
+            !surveys.is_empty()
         });
         
         // Clear expired scans
         self.scans.retain(|_symbol, scan| {
             now < scan.expiration
         });
+        
+        // Clear cached data past its stale window
+        let policy = self.policy;
+        for (category, entries) in self.cache.iter_mut() {
+            if let Some(ttl) = policy.ttl(*category) {
+                entries.retain(|_key, entry| now < entry.fetched_at + ttl + policy.max_stale_seconds);
+            }
+        }
     }
 
     /// Gets the number of stored statuses
@@ -214,43 +563,54 @@ impl StatusStorage {
         self.statuses.is_empty() && self.surveys.is_empty() && self.scans.is_empty()
     }
 
-    /// Updates or creates a survey
+    /// Adds a survey, replacing any stored survey with the same signature
     pub fn update_survey(&mut self, survey: Survey) {
+        let surveys = self.surveys.entry(survey.symbol.clone()).or_default();
+        surveys.retain(|stored| stored.signature != survey.signature);
+        surveys.push(survey);
+    }
+
+    /// Gets the valid surveys of a waypoint
+    pub fn get_surveys(&self, waypoint_symbol: &str) -> Vec<Survey> {
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap_or_default()
             .as_secs();
-        
-        // Set expiration time if not already set
-        let mut survey = survey;
-        if survey.expiration == 0 {
-            survey.expiration = now + self.max_age_seconds;
-        }
-        
-        self.surveys.insert(survey.symbol.clone(), survey);
+
+        self.surveys
+            .get(waypoint_symbol)
+            .into_iter()
+            .flatten()
+            .filter(|survey| survey_is_valid(survey, now))
+            .cloned()
+            .collect()
     }
 
-    /// Gets a survey by waypoint symbol
-    pub fn get_survey(&self, waypoint_symbol: &str) -> Option<Survey> {
-        self.surveys.get(waypoint_symbol).cloned()
+    /// Gets a survey by signature
+    pub fn get_survey(&self, signature: &str) -> Option<Survey> {
+        self.surveys
+            .values()
+            .flatten()
+            .find(|survey| survey.signature == signature)
+            .cloned()
     }
 
-    /// Removes a survey from storage
-    pub fn remove_survey(&mut self, waypoint_symbol: &str) {
-        self.surveys.remove(waypoint_symbol);
+    /// Removes a survey from storage, e.g. once it is exhausted
+    pub fn remove_survey(&mut self, signature: &str) {
+        for surveys in self.surveys.values_mut() {
+            surveys.retain(|survey| survey.signature != signature);
+        }
+        self.surveys.retain(|_symbol, surveys| !surveys.is_empty());
     }
 
     /// Checks if a survey is still valid (not expired)
-    pub fn is_survey_valid(&self, waypoint_symbol: &str) -> bool {
-        if let Some(survey) = self.surveys.get(waypoint_symbol) {
-            let now = SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_secs();
-            now < survey.expiration
-        } else {
-            false
-        }
+    pub fn is_survey_valid(&self, signature: &str) -> bool {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        self.get_survey(signature)
+            .is_some_and(|survey| survey_is_valid(&survey, now))
     }
 
     /// Updates or creates a scan
@@ -263,7 +623,7 @@ impl StatusStorage {
         // Set expiration time if not already set
         let mut scan = scan;
         if scan.expiration == 0 {
-            scan.expiration = now + self.max_age_seconds;
+            scan.expiration = self.policy.waypoints_ttl.map_or(u64::MAX, |ttl| now + ttl);
         }
         
         self.scans.insert(scan.symbol.clone(), scan);
@@ -301,7 +661,8 @@ impl StatusStorage {
         
         self.surveys
             .values()
-            .filter(|survey| now < survey.expiration)
+            .flatten()
+            .filter(|survey| survey_is_valid(survey, now))
             .cloned()
             .collect()
     }
@@ -321,6 +682,44 @@ impl StatusStorage {
     }
 }
 
+/// Cargo items of a ship's hold
+fn cargo_items(cargo: &ShipCargo) -> Vec<CargoItem> {
+    cargo
+        .inventory
+        .iter()
+        .map(|item| CargoItem {
+            trade_symbol: item.symbol.clone(),
+            units: item.units,
+        })
+        .collect()
+}
+
+/// Writes `contents` to a temporary file next to `path`, flushes it and renames it over `path`
+///
+/// Every write gets its own temporary file, so concurrent writers never share one.
+pub(crate) fn write_atomically(path: &Path, contents: &[u8]) -> io::Result<()> {
+    static WRITES: AtomicU64 = AtomicU64::new(0);
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(format!(".{}.{}.tmp", std::process::id(), WRITES.fetch_add(1, Ordering::Relaxed)));
+    let mut file = fs::File::create(&temp_path)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    fs::rename(&temp_path, path)
+}
+
+/// Current Unix time in seconds
+pub(crate) fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// A survey is valid until its expiration; one we cannot parse is treated as expired
+fn survey_is_valid(survey: &Survey, now: u64) -> bool {
+    survey.expires_at().is_some_and(|expires_at| now < expires_at)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -413,18 +812,31 @@ mod tests {
     fn test_survey_storage() {
         let mut storage = StatusStorage::new();
         
-        let survey = Survey {
+        let survey = |signature: &str, expiration: &str| Survey {
+            signature: signature.to_string(),
             symbol: "X1-ABCD-1234".to_string(),
-            deposits: vec!["IRON_ORE".to_string(), "SILVER".to_string()],
-            expiration: 3600,
+            deposits: vec![
+                SurveyDeposit { symbol: "IRON_ORE".to_string() },
+                SurveyDeposit { symbol: "SILVER_ORE".to_string() },
+            ],
+            expiration: expiration.to_string(),
             size: SurveySize::Large,
         };
         
-        storage.update_survey(survey.clone());
-        let retrieved = storage.get_survey("X1-ABCD-1234");
+        storage.update_survey(survey("X1-ABCD-1234-5E6F", "2099-01-01T00:00:00.000Z"));
+        storage.update_survey(survey("X1-ABCD-1234-7A8B", "2099-01-01T00:00:00.000Z"));
+        storage.update_survey(survey("X1-ABCD-1234-9C0D", "2020-01-01T00:00:00.000Z"));
+        storage.update_survey(survey("X1-ABCD-1234-5E6F", "2099-01-01T00:00:00.000Z"));
         
-        assert!(retrieved.is_some());
-        assert_eq!(retrieved.unwrap().symbol, "X1-ABCD-1234");
+        assert_eq!(storage.get_surveys("X1-ABCD-1234").len(), 2);
+        assert!(storage.is_survey_valid("X1-ABCD-1234-7A8B"));
+        assert!(!storage.is_survey_valid("X1-ABCD-1234-9C0D"));
+        
+        storage.remove_survey("X1-ABCD-1234-5E6F");
+        storage.clear_expired();
+        
+        assert_eq!(storage.get_all_valid_surveys().len(), 1);
+        assert!(storage.get_survey("X1-ABCD-1234-9C0D").is_none());
     }
 
     #[test]
@@ -454,4 +866,124 @@ mod tests {
         assert_eq!(scan.symbol, "X1-ABCD-1234");
         assert_eq!(scan.materials.len(), 2);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_status_from_api_ship() {
+        let mut ship_json = crate::mock_server::ship_json("SHIP-1");
+        ship_json["nav"]["status"] = serde_json::json!("IN_TRANSIT");
+        ship_json["nav"]["route"]["arrival"] = serde_json::json!("2026-10-16T12:05:00.000Z");
+        let ship: Ship = serde_json::from_value(ship_json).unwrap();
+
+        let mut storage = StatusStorage::new();
+        storage.update_from_ship(&ship);
+        let status = storage.statuses.get("SHIP-1").unwrap();
+
+        assert_eq!(status.status_type, ShipStatusType::Traveling);
+        assert_eq!(status.expires_at, Some(1_792_152_300));
+        assert_eq!(status.location, "X1-DF55-20250Z");
+        assert_eq!(status.fuel, 350);
+        assert_eq!(status.cargo[0].trade_symbol, "IRON_ORE");
+        assert_eq!(status.cargo[0].units, 10);
+    }
+
+    #[test]
+    fn test_snapshot_round_trip_drops_expired_entries() {
+        let path = std::env::temp_dir().join(format!("status_storage_{}_round_trip.json", std::process::id()));
+        let survey = |signature: &str, expiration: &str| Survey {
+            signature: signature.to_string(),
+            symbol: "X1-ABCD-1234".to_string(),
+            deposits: vec![SurveyDeposit { symbol: "IRON_ORE".to_string() }],
+            expiration: expiration.to_string(),
+            size: SurveySize::Small,
+        };
+
+        let mut storage = StatusStorage::new();
+        storage.update_status(ShipStatus {
+            ship_symbol: "SHIP-123".to_string(),
+            status_type: ShipStatusType::Mining,
+            location: "X1-ABCD-1234".to_string(),
+            cargo: vec![CargoItem { trade_symbol: "IRON_ORE".to_string(), units: 5 }],
+            fuel: 100,
+            last_updated: 0,
+            expires_at: None,
+        });
+        storage.update_survey(survey("FRESH", "2099-01-01T00:00:00.000Z"));
+        storage.update_survey(survey("STALE", "2020-01-01T00:00:00.000Z"));
+        storage.cache_put(CacheCategory::Systems, "X1-ABCD", &"system").unwrap();
+        storage.save(&path).unwrap();
+
+        let loaded = StatusStorage::load(&path, CachePolicy::default()).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let status = loaded.get_status("SHIP-123").unwrap();
+        assert_eq!(status.status_type, ShipStatusType::Mining);
+        assert_eq!(status.cargo[0].units, 5);
+        assert!(loaded.get_survey("FRESH").is_some());
+        assert!(loaded.get_survey("STALE").is_none());
+        assert_eq!(loaded.cache_get(CacheCategory::Systems, "X1-ABCD"), Freshness::Fresh("system".to_string()));
+    }
+
+    #[test]
+    fn test_load_expires_cache_by_the_given_policy() {
+        let path = std::env::temp_dir().join(format!("status_storage_{}_policy.json", std::process::id()));
+        let mut storage = StatusStorage::new();
+        storage.cache_put(CacheCategory::Markets, "X1-ABCD-1234", &"market").unwrap();
+        // Two hours old: past the default markets TTL and stale window
+        for entry in storage.cache.values_mut().flat_map(|entries| entries.values_mut()) {
+            entry.fetched_at -= 2 * 3600;
+        }
+        storage.save(&path).unwrap();
+
+        let policy = CachePolicy { markets_ttl: Some(24 * 3600), ..CachePolicy::default() };
+        let kept = StatusStorage::load(&path, policy).unwrap();
+        let dropped = StatusStorage::load(&path, CachePolicy::default()).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(kept.policy(), policy);
+        assert_eq!(kept.cache_get(CacheCategory::Markets, "X1-ABCD-1234"), Freshness::Fresh("market".to_string()));
+        assert_eq!(dropped.cache_fetched_at(CacheCategory::Markets, "X1-ABCD-1234"), None);
+    }
+
+    #[test]
+    fn test_load_missing_snapshot_starts_empty() {
+        let path = std::env::temp_dir().join(format!("status_storage_{}_missing.json", std::process::id()));
+        assert!(StatusStorage::load(&path, CachePolicy::default()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_load_rejects_unknown_snapshot_version() {
+        let path = std::env::temp_dir().join(format!("status_storage_{}_version.json", std::process::id()));
+        fs::write(&path, r#"{"version": 99, "max_age_seconds": 300, "statuses": {}, "surveys": {}, "scans": {}}"#).unwrap();
+
+        let result = StatusStorage::load(&path, CachePolicy::default());
+        fs::remove_file(&path).unwrap();
+
+        assert!(matches!(result, Err(SpaceTradersError::Io(_))));
+    }
+
+    #[test]
+    fn test_cache_freshness_follows_category_ttl() {
+        let mut storage = StatusStorage::with_policy(CachePolicy {
+            markets_ttl: Some(0),
+            waypoints_ttl: Some(0),
+            max_stale_seconds: 0,
+            ..CachePolicy::default()
+        });
+        storage.cache_put(CacheCategory::Systems, "X1-DF55", &"system").unwrap();
+        storage.cache_put(CacheCategory::Markets, "X1-DF55-A1", &"market").unwrap();
+        storage.cache_put(CacheCategory::Waypoints, "X1-DF55-A1", &"waypoint").unwrap();
+
+        // Systems never expire; a zero TTL without a stale window is gone at once
+        assert_eq!(storage.cache_get(CacheCategory::Systems, "X1-DF55"), Freshness::Fresh("system".to_string()));
+        assert_eq!(storage.cache_get::<String>(CacheCategory::Waypoints, "X1-DF55-A1"), Freshness::Missing);
+
+        storage.set_policy(CachePolicy { markets_ttl: Some(0), ..CachePolicy::default() });
+        assert_eq!(storage.cache_get(CacheCategory::Markets, "X1-DF55-A1"), Freshness::Stale("market".to_string()));
+        assert_eq!(storage.cache_get::<String>(CacheCategory::Shipyards, "X1-DF55-A1"), Freshness::Missing);
+
+        storage.set_policy(CachePolicy { markets_ttl: Some(0), max_stale_seconds: 0, ..CachePolicy::default() });
+        storage.clear_expired();
+        assert_eq!(storage.cache_fetched_at(CacheCategory::Markets, "X1-DF55-A1"), None);
+        assert!(storage.cache_fetched_at(CacheCategory::Systems, "X1-DF55").is_some());
+    }
+}
@@ -0,0 +1,76 @@
+//! Systems and waypoints lookups
+
+use crate::client::ApiClient;
+use crate::error::SpaceTradersError;
+use crate::models::{ApiResponse, System, Waypoint};
+
+/// Gets a single system
+pub async fn get_system(
+    client: &ApiClient,
+    system_symbol: &str
+) -> Result<System, SpaceTradersError> {
+    let response: ApiResponse<System> = client.get(&format!("/systems/{}", system_symbol)).await?;
+    Ok(response.data)
+}
+
+/// Gets every waypoint in a system, including traits, across all pages
+pub async fn list_waypoints(
+    client: &ApiClient,
+    system_symbol: &str
+) -> Result<Vec<Waypoint>, SpaceTradersError> {
+    client.get_all(&format!("/systems/{}/waypoints", system_symbol)).await
+}
+
+/// Gets the first system listed by the API, used when no other system is known
+pub async fn first_system(client: &ApiClient) -> Result<Option<System>, SpaceTradersError> {
+    let mut pages = client.paginate::<System>("/systems");
+
+    while let Some(page) = pages.next_page().await? {
+        if let Some(system) = page.into_iter().next() {
+            return Ok(Some(system));
+        }
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_server::{MockResponse, MockServer};
+    use serde_json::json;
+
+    fn waypoint(symbol: &str) -> serde_json::Value {
+        json!({
+            "symbol": symbol,
+            "type": "ASTEROID",
+            "systemSymbol": "X1-DF55",
+            "x": 10,
+            "y": -4,
+            "orbitals": [],
+            "traits": [{"symbol": "COMMON_METAL_DEPOSITS", "name": "Common Metal Deposits", "description": ""}],
+            "isUnderConstruction": false
+        })
+    }
+
+    #[tokio::test]
+    async fn test_list_waypoints_reads_every_page() {
+        let first: Vec<_> = (0..20).map(|i| waypoint(&format!("X1-DF55-A{}", i))).collect();
+        let server = MockServer::start().await;
+        server.route("GET", "/systems/X1-DF55/waypoints?page=1&limit=20", vec![MockResponse::json(200, json!({
+            "data": first,
+            "meta": {"total": 21, "page": 1, "limit": 20}
+        }))]);
+        server.route("GET", "/systems/X1-DF55/waypoints?page=2&limit=20", vec![MockResponse::json(200, json!({
+            "data": [waypoint("X1-DF55-B1")],
+            "meta": {"total": 21, "page": 2, "limit": 20}
+        }))]);
+
+        let client = ApiClient::with_base_url(server.base_url(), Some("test_token"));
+        let waypoints = list_waypoints(&client, "X1-DF55").await.unwrap();
+
+        assert_eq!(waypoints.len(), 21);
+        assert_eq!(waypoints[20].symbol, "X1-DF55-B1");
+        assert!(waypoints[20].has_trait("COMMON_METAL_DEPOSITS"));
+    }
+}
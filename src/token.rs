@@ -2,8 +2,10 @@
 
 use std::fs;
 
+use crate::error::SpaceTradersError;
+
 /// Reads the agent token from file
-pub fn read_token() -> Result<String, Box<dyn std::error::Error>> {
+pub fn read_token() -> Result<String, SpaceTradersError> {
     let token_content = fs::read_to_string("AGENT_TOKEN")?;
     let clean_token = token_content.trim();
     
@@ -0,0 +1,281 @@
+//! Finding profitable trade routes between known markets
+//!
+//! Routes are planned from the price history, so markets no ship is docked at
+//! take part with their last observed prices.
+
+use std::collections::HashMap;
+
+use crate::distance::{Waypoint, fuel_cost, travel_seconds};
+use crate::models::{Ship, ShipNavFlightMode};
+use crate::price_history::{PriceHistory, PricePoint};
+
+/// What the planner needs to know about the ship flying a route
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShipProfile {
+    pub cargo_capacity: i32,
+    /// Zero for ships that do not use fuel
+    pub fuel_capacity: i32,
+    pub speed: i32,
+}
+
+impl From<&Ship> for ShipProfile {
+    fn from(ship: &Ship) -> Self {
+        Self {
+            cargo_capacity: ship.cargo.capacity,
+            fuel_capacity: ship.fuel.capacity,
+            speed: ship.engine.speed,
+        }
+    }
+}
+
+/// Costs the planner applies to every route
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RouteOptions {
+    /// Credits per unit of ship fuel
+    pub fuel_price: f64,
+    /// How much each trade-volume batch moves a price against us, e.g. 0.05 for 5%
+    pub slippage: f64,
+}
+
+impl Default for RouteOptions {
+    fn default() -> Self {
+        Self {
+            fuel_price: 1.0,
+            slippage: 0.05,
+        }
+    }
+}
+
+/// One order of a route, bought at `buy_at` and sold at `sell_at`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TradeBatch {
+    pub units: i32,
+    /// Expected purchase price after our earlier orders
+    pub buy_price: i64,
+    /// Expected sell price after our earlier orders
+    pub sell_price: i64,
+}
+
+/// Buying a good at one market and selling it at another
+#[derive(Debug, Clone, PartialEq)]
+pub struct TradeRoute {
+    pub trade_symbol: String,
+    pub buy_at: String,
+    pub sell_at: String,
+    /// Observed purchase price at `buy_at` before our own trades
+    pub buy_price: i64,
+    /// Observed sell price at `sell_at` before our own trades
+    pub sell_price: i64,
+    pub units: i32,
+    /// Orders the units are bought and sold in, first to last
+    pub batches: Vec<TradeBatch>,
+    pub buy_cost: i64,
+    pub revenue: i64,
+    /// Fuel for the flight to `buy_at` and on to `sell_at`, in credits
+    pub fuel_cost: f64,
+    /// Seconds flying to `buy_at` and on to `sell_at`
+    pub travel_seconds: u64,
+    /// Revenue minus purchases and fuel
+    pub profit: f64,
+    pub profit_per_second: f64,
+}
+
+/// Ranks every profitable route between markets in the price history
+///
+/// Each market contributes its latest observation of each good. Goods are
+/// traded in batches of at most the smaller trade volume of the two markets;
+/// every batch moves both prices by `options.slippage`, and batches stop once
+/// they no longer make a profit or the hold is full. Fuel and travel time
+/// include the ship's own flight from `from` to the source market, so a
+/// nearby route can beat a richer one across the system; if `from` has no
+/// coordinates in `waypoints` that flight is left out. Routes between markets
+/// without coordinates, or with a leg longer than a full tank reaches, are
+/// left out. The best profit per second comes first.
+pub fn find_trade_routes(
+    history: &PriceHistory,
+    waypoints: &[Waypoint],
+    from: &str,
+    ship: &ShipProfile,
+    options: &RouteOptions
+) -> Vec<TradeRoute> {
+    let positions: HashMap<&str, &Waypoint> = waypoints.iter().map(|w| (w.symbol.as_str(), w)).collect();
+    let start = positions.get(from).copied();
+    let mut routes = Vec::new();
+
+    for trade_symbol in history.trade_symbols() {
+        let markets: Vec<(&Waypoint, &PricePoint)> = history
+            .markets_trading(trade_symbol)
+            .into_iter()
+            .filter_map(|symbol| Some((*positions.get(symbol)?, history.latest(symbol, trade_symbol)?)))
+            .collect();
+
+        for &(buy_at, bought) in &markets {
+            for &(sell_at, sold) in &markets {
+                if buy_at.symbol == sell_at.symbol {
+                    continue;
+                }
+                if let Some(route) = plan_route(trade_symbol, start, (buy_at, bought), (sell_at, sold), ship, options) {
+                    routes.push(route);
+                }
+            }
+        }
+    }
+
+    routes.sort_by(|a, b| {
+        b.profit_per_second
+            .partial_cmp(&a.profit_per_second)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    routes
+}
+
+/// Plans one route, or `None` if it is unreachable or makes no profit
+fn plan_route(
+    trade_symbol: &str,
+    start: Option<&Waypoint>,
+    (buy_at, bought): (&Waypoint, &PricePoint),
+    (sell_at, sold): (&Waypoint, &PricePoint),
+    ship: &ShipProfile,
+    options: &RouteOptions
+) -> Option<TradeRoute> {
+    let (approach_fuel, approach_seconds) = match start {
+        Some(start) if start.symbol != buy_at.symbol => {
+            let distance = start.distance_to(buy_at);
+            (cruise_fuel(distance, ship), travel_seconds(distance, ship.speed, ShipNavFlightMode::Cruise))
+        }
+        _ => (0, 0),
+    };
+    let distance = buy_at.distance_to(sell_at);
+    let fuel = cruise_fuel(distance, ship);
+    if fuel > ship.fuel_capacity || approach_fuel > ship.fuel_capacity {
+        return None;
+    }
+
+    let batch_size = bought.trade_volume.min(sold.trade_volume).max(1);
+    let (mut units, mut buy_cost, mut revenue) = (0, 0, 0);
+    let mut batches = Vec::new();
+    while units < ship.cargo_capacity {
+        let batch = TradeBatch {
+            units: batch_size.min(ship.cargo_capacity - units),
+            buy_price: slipped_price(bought.purchase_price, options.slippage, batches.len()),
+            sell_price: slipped_price(sold.sell_price, -options.slippage, batches.len()),
+        };
+        if batch.sell_price <= batch.buy_price {
+            break;
+        }
+
+        units += batch.units;
+        buy_cost += batch.buy_price * batch.units as i64;
+        revenue += batch.sell_price * batch.units as i64;
+        batches.push(batch);
+    }
+
+    let fuel_cost = (approach_fuel + fuel) as f64 * options.fuel_price;
+    let profit = (revenue - buy_cost) as f64 - fuel_cost;
+    if units == 0 || profit <= 0.0 {
+        return None;
+    }
+    let travel_seconds = approach_seconds + travel_seconds(distance, ship.speed, ShipNavFlightMode::Cruise);
+
+    Some(TradeRoute {
+        trade_symbol: trade_symbol.to_string(),
+        buy_at: buy_at.symbol.clone(),
+        sell_at: sell_at.symbol.clone(),
+        buy_price: bought.purchase_price,
+        sell_price: sold.sell_price,
+        units,
+        batches,
+        buy_cost,
+        revenue,
+        fuel_cost,
+        travel_seconds,
+        profit,
+        profit_per_second: profit / travel_seconds.max(1) as f64,
+    })
+}
+
+/// Expected price of the `batch`-th order after our earlier orders moved it by `slippage` each
+fn slipped_price(price: i64, slippage: f64, batch: usize) -> i64 {
+    (price as f64 * (1.0 + slippage * batch as f64)).round() as i64
+}
+
+/// Fuel burnt cruising a distance; ships without a tank burn none
+fn cruise_fuel(distance: f64, ship: &ShipProfile) -> i32 {
+    if ship.fuel_capacity == 0 {
+        return 0;
+    }
+    fuel_cost(distance, ShipNavFlightMode::Cruise)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_server::trade_good;
+
+    fn ship() -> ShipProfile {
+        ShipProfile { cargo_capacity: 40, fuel_capacity: 400, speed: 10 }
+    }
+
+    #[test]
+    fn test_routes_account_for_slippage_and_fuel() {
+        let mut history = PriceHistory::new();
+        history.record("X1-DF55-A", 100, &[trade_good("IRON", 100, 90, 10)]);
+        history.record("X1-DF55-B", 100, &[trade_good("IRON", 130, 120, 20)]);
+        let waypoints = vec![
+            Waypoint::new("X1-DF55-A".to_string(), 0, 0),
+            Waypoint::new("X1-DF55-B".to_string(), 30, 40),
+        ];
+
+        let routes = find_trade_routes(&history, &waypoints, "X1-DF55-A", &ship(), &RouteOptions::default());
+
+        assert_eq!(routes.len(), 1);
+        let route = &routes[0];
+        assert_eq!((route.buy_at.as_str(), route.sell_at.as_str()), ("X1-DF55-A", "X1-DF55-B"));
+        // Batches of 10 at 100/120, 105/114, 110/108 (unprofitable, not bought)
+        assert_eq!(route.units, 20);
+        assert_eq!(route.batches, [
+            TradeBatch { units: 10, buy_price: 100, sell_price: 120 },
+            TradeBatch { units: 10, buy_price: 105, sell_price: 114 },
+        ]);
+        assert_eq!((route.buy_cost, route.revenue), (2_050, 2_340));
+        assert_eq!(route.fuel_cost, 50.0);
+        assert_eq!(route.travel_seconds, 140);
+        assert_eq!(route.profit, 240.0);
+
+        // Starting at the destination adds the flight back to the source
+        let routes = find_trade_routes(&history, &waypoints, "X1-DF55-B", &ship(), &RouteOptions::default());
+        assert_eq!(routes[0].fuel_cost, 100.0);
+        assert_eq!(routes[0].travel_seconds, 280);
+        assert_eq!(routes[0].profit, 190.0);
+    }
+
+    #[test]
+    fn test_routes_ranked_by_profit_per_second() {
+        let mut history = PriceHistory::new();
+        history.record("X1-DF55-A", 100, &[trade_good("IRON", 10, 8, 100), trade_good("GOLD", 10, 8, 100)]);
+        history.record("X1-DF55-NEAR", 100, &[trade_good("IRON", 30, 25, 100)]);
+        history.record("X1-DF55-FAR", 100, &[trade_good("GOLD", 40, 35, 100)]);
+        history.record("X1-DF55-UNKNOWN", 100, &[trade_good("GOLD", 500, 400, 100)]);
+        let waypoints = vec![
+            Waypoint::new("X1-DF55-A".to_string(), 0, 0),
+            Waypoint::new("X1-DF55-NEAR".to_string(), 10, 0),
+            Waypoint::new("X1-DF55-FAR".to_string(), 300, 0),
+        ];
+
+        let routes = find_trade_routes(&history, &waypoints, "X1-DF55-A", &ship(), &RouteOptions::default());
+
+        // GOLD makes more per trip, but IRON's short hop earns more per second
+        assert_eq!(routes.len(), 2);
+        assert_eq!(routes[0].trade_symbol, "IRON");
+        assert!(routes[1].profit > routes[0].profit);
+        assert!(routes.iter().all(|r| r.sell_at != "X1-DF55-UNKNOWN"));
+
+        let small_tank = ShipProfile { fuel_capacity: 100, ..ship() };
+        let routes = find_trade_routes(&history, &waypoints, "X1-DF55-A", &small_tank, &RouteOptions::default());
+        assert_eq!(routes.len(), 1);
+
+        // From across the system the flight out to the cheap market dominates both routes
+        let routes = find_trade_routes(&history, &waypoints, "X1-DF55-FAR", &ship(), &RouteOptions::default());
+        assert!(routes.iter().all(|r| r.travel_seconds > 765));
+    }
+}
@@ -0,0 +1,574 @@
+//! Trader behavior: flying planned trade routes end to end
+//!
+//! Each trip plans the best route from the price history in `StatusStorage`,
+//! flies to the source market, buys in the route's planned batches, flies to
+//! the destination and sells. Cargo an earlier trip could not sell is sold at
+//! its best known market first. Every market visit updates the price history, so
+//! the next trip is planned with what the ship just saw.
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use crate::client::ApiClient;
+use crate::distance::Waypoint;
+use crate::error::SpaceTradersError;
+use crate::markets::{SellPrices, observe_market};
+use crate::models::{MarketTransaction, Ship, ShipCargoItem, ShipFuel, ShipNavStatus, time_until};
+use crate::navigation::{dock_ship, navigate_ship, orbit_ship, set_flight_mode};
+use crate::pathfinding::{FlightPlan, FlightProfile, plan_flight};
+use crate::price_history::PriceHistory;
+use crate::shared_storage::SharedStatusStorage;
+use crate::ships::get_ship;
+use crate::status_storage::{ShipStatusType, unix_now};
+use crate::trade_routes::{RouteOptions, ShipProfile, TradeRoute, find_trade_routes};
+use crate::trading::{purchase_cargo, refuel_ship, sell_all_cargo};
+
+/// How a trader plans routes and when it gives up on one
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TraderOptions {
+    pub route: RouteOptions,
+    /// How far an observed price may move against the plan, e.g. 0.1 for 10%
+    pub price_tolerance: f64,
+}
+
+impl Default for TraderOptions {
+    fn default() -> Self {
+        Self {
+            route: RouteOptions::default(),
+            price_tolerance: 0.1,
+        }
+    }
+}
+
+/// Result of one trade trip
+#[derive(Debug, Clone)]
+pub enum TradeOutcome {
+    /// The route was flown; some orders may have stopped early on price
+    Completed {
+        route: TradeRoute,
+        transactions: Vec<MarketTransaction>,
+    },
+    /// A leg was abandoned because prices moved beyond the tolerance
+    Aborted {
+        route: TradeRoute,
+        transactions: Vec<MarketTransaction>,
+        reason: String,
+    },
+    /// No profitable route is known
+    NoRoute,
+}
+
+/// Flies up to `max_trips` trade trips with a ship, re-planning after each one
+///
+/// Stops early once no profitable route is known.
+pub async fn run_trader(
+    client: &ApiClient,
+    storage: &SharedStatusStorage,
+    ship_symbol: &str,
+    waypoints: &[Waypoint],
+    options: &TraderOptions,
+    max_trips: usize
+) -> Result<Vec<TradeOutcome>, SpaceTradersError> {
+    let mut outcomes = Vec::new();
+
+    for _ in 0..max_trips {
+        let outcome = trade_once(client, storage, ship_symbol, waypoints, options).await?;
+        let done = matches!(outcome, TradeOutcome::NoRoute);
+        outcomes.push(outcome);
+        if done {
+            break;
+        }
+    }
+
+    Ok(outcomes)
+}
+
+/// Plans the best route for a ship's free cargo space and flies it
+///
+/// Both flights are planned with `plan_flight`, refuelling at markets the
+/// price history has seen selling fuel. On reaching the source market the
+/// destination's latest sell price is checked against the plan before
+/// anything is bought.
+pub async fn trade_once(
+    client: &ApiClient,
+    storage: &SharedStatusStorage,
+    ship_symbol: &str,
+    waypoints: &[Waypoint],
+    options: &TraderOptions
+) -> Result<TradeOutcome, SpaceTradersError> {
+    // A ship still traveling according to the storage cannot trade yet
+    if let Some(status) = storage.get_status(ship_symbol).await
+        && status.status_type == ShipStatusType::Traveling
+    {
+        sleep_until(status.expires_at).await;
+    }
+
+    let mut ship = get_ship(client, ship_symbol).await?;
+    storage.update_from_ship(&ship).await;
+    if ship.nav.status == ShipNavStatus::InTransit {
+        sleep_until_arrival(&ship.nav.route.arrival).await;
+        ship.nav.status = ShipNavStatus::InOrbit;
+    }
+
+    let fuel_stations: HashSet<String> = {
+        let storage = storage.read().await;
+        storage.price_history().markets_trading("FUEL").into_iter().map(String::from).collect()
+    };
+    sell_leftover_cargo(client, storage, &mut ship, waypoints, &fuel_stations).await?;
+
+    let profile = ShipProfile {
+        cargo_capacity: ship.cargo.capacity - ship.cargo.units,
+        ..ShipProfile::from(&ship)
+    };
+    let route = {
+        let storage = storage.read().await;
+        find_trade_routes(storage.price_history(), waypoints, &ship.nav.waypoint_symbol, &profile, &options.route)
+            .into_iter()
+            .next()
+    };
+    let Some(route) = route else {
+        return Ok(TradeOutcome::NoRoute);
+    };
+    println!(
+        "{} trading {} {} from {} to {} for {:.0} credits",
+        ship_symbol, route.units, route.trade_symbol, route.buy_at, route.sell_at, route.profit
+    );
+
+    let location = ship.nav.waypoint_symbol.clone();
+    let Some(approach) = plan_flight(waypoints, &fuel_stations, &location, &route.buy_at, &FlightProfile::from(&ship)) else {
+        let reason = format!("{} cannot reach {} on the fuel available", ship_symbol, route.buy_at);
+        return Ok(TradeOutcome::Aborted { route, transactions: Vec::new(), reason });
+    };
+    fly_plan(client, storage, &mut ship, &approach).await?;
+
+    // Another ship may have seen the destination since the route was planned
+    let latest_sell = storage
+        .read()
+        .await
+        .price_history()
+        .latest(&route.sell_at, &route.trade_symbol)
+        .map(|point| point.sell_price);
+    let lowest_accepted = (route.sell_price as f64 * (1.0 - options.price_tolerance)).ceil() as i64;
+    if let Some(price) = latest_sell.filter(|&price| price < lowest_accepted) {
+        let reason = format!("{} sell price at {} fell to {} before buying", route.trade_symbol, route.sell_at, price);
+        return Ok(TradeOutcome::Aborted { route, transactions: Vec::new(), reason });
+    }
+
+    // Plan the delivery before buying, so no cargo is bought that cannot be delivered
+    let delivery = plan_flight(waypoints, &fuel_stations, &route.buy_at, &route.sell_at, &FlightProfile::from(&ship));
+    let Some(delivery) = delivery else {
+        let reason = format!("{} cannot reach {} on the fuel available", ship_symbol, route.sell_at);
+        return Ok(TradeOutcome::Aborted { route, transactions: Vec::new(), reason });
+    };
+
+    let mut transactions = buy_leg(client, storage, ship_symbol, &route, options).await?;
+    if transactions.is_empty() {
+        let reason = format!("{} purchase price moved beyond the plan at {}", route.trade_symbol, route.buy_at);
+        return Ok(TradeOutcome::Aborted { route, transactions, reason });
+    }
+
+    fly_plan(client, storage, &mut ship, &delivery).await?;
+
+    // Only sell the routed good; anything else was cargo the hold could not be cleared of
+    let mut ship = get_ship(client, ship_symbol).await?;
+    ship.cargo.inventory.retain(|item| item.symbol == route.trade_symbol);
+    let lowest_planned = route.batches.last().map_or(route.sell_price, |batch| batch.sell_price);
+    let floor = (lowest_planned as f64 * (1.0 - options.price_tolerance)).ceil() as i64;
+    let floors = SellPrices::from([(route.trade_symbol.clone(), floor)]);
+
+    let sales = sell_all_cargo(client, storage, &ship, &floors).await?;
+    let sold_any = !sales.is_empty();
+    transactions.extend(sales);
+    if !sold_any {
+        let reason = format!("{} sell price at {} fell below {}", route.trade_symbol, route.sell_at, floor);
+        return Ok(TradeOutcome::Aborted { route, transactions, reason });
+    }
+
+    Ok(TradeOutcome::Completed { route, transactions })
+}
+
+/// Sells cargo left in the hold, e.g. by an aborted trip, before a new route is planned
+///
+/// The most valuable good goes to the market with its best last observed sell
+/// price, which also buys whatever else in the hold it trades, until the hold
+/// is empty. Goods no market with coordinates in `waypoints` is known to buy,
+/// or that the fuel on board cannot reach, stay in the hold. Sales are
+/// recorded in the storage.
+async fn sell_leftover_cargo(
+    client: &ApiClient,
+    storage: &SharedStatusStorage,
+    ship: &mut Ship,
+    waypoints: &[Waypoint],
+    fuel_stations: &HashSet<String>
+) -> Result<(), SpaceTradersError> {
+    loop {
+        let target = best_market_for(storage.read().await.price_history(), waypoints, &ship.cargo.inventory);
+        let Some(market_symbol) = target else {
+            return Ok(());
+        };
+        let location = ship.nav.waypoint_symbol.clone();
+        let Some(flight) = plan_flight(waypoints, fuel_stations, &location, &market_symbol, &FlightProfile::from(&*ship)) else {
+            println!("{} cannot reach {} to sell its leftover cargo", ship.symbol, market_symbol);
+            return Ok(());
+        };
+
+        fly_plan(client, storage, ship, &flight).await?;
+        let sales = sell_all_cargo(client, storage, ship, &SellPrices::new()).await?;
+        *ship = get_ship(client, &ship.symbol).await?;
+        if sales.is_empty() {
+            return Ok(());
+        }
+    }
+}
+
+/// Market paying the most for the most valuable good in a hold, by last observed prices
+fn best_market_for(history: &PriceHistory, waypoints: &[Waypoint], inventory: &[ShipCargoItem]) -> Option<String> {
+    inventory
+        .iter()
+        .flat_map(|item| {
+            waypoints.iter().filter_map(|waypoint| {
+                let point = history.latest(&waypoint.symbol, &item.symbol)?;
+                Some((&waypoint.symbol, point.sell_price * item.units as i64))
+            })
+        })
+        .max_by_key(|&(_, value)| value)
+        .map(|(symbol, _)| symbol.clone())
+}
+
+/// Buys a route's planned batches while prices stay within tolerance
+///
+/// Each order is checked against its own batch's planned price, and never
+/// exceeds the trade volume the market currently shows. Running out of
+/// credits or cargo space ends the purchases without an error.
+async fn buy_leg(
+    client: &ApiClient,
+    storage: &SharedStatusStorage,
+    ship_symbol: &str,
+    route: &TradeRoute,
+    options: &TraderOptions
+) -> Result<Vec<MarketTransaction>, SpaceTradersError> {
+    let mut transactions = Vec::new();
+
+    for batch in &route.batches {
+        let market = observe_market(client, storage, &route.buy_at).await?.market;
+        let Some(good) = market.trade_goods.iter().flatten().find(|g| g.symbol == route.trade_symbol) else {
+            break;
+        };
+
+        if good.purchase_price as f64 > batch.buy_price as f64 * (1.0 + options.price_tolerance) {
+            println!(
+                "{} costs {} at {}, more than the planned {}; stopping purchases",
+                route.trade_symbol, good.purchase_price, route.buy_at, batch.buy_price
+            );
+            break;
+        }
+
+        let units = batch.units.min(good.trade_volume.max(1));
+        let result = match purchase_cargo(client, ship_symbol, &route.trade_symbol, units).await {
+            Ok(result) => result,
+            // Whatever was bought so far is still worth delivering
+            Err(
+                e @ (SpaceTradersError::InsufficientFunds(_)
+                | SpaceTradersError::TradeLimitExceeded(_)
+                | SpaceTradersError::CargoFull(_))
+            ) => {
+                println!("Stopping purchases of {} at {}: {}", route.trade_symbol, route.buy_at, e);
+                break;
+            }
+            Err(e) => return Err(e),
+        };
+        storage.record_trade(&result).await;
+        transactions.push(result.transaction);
+    }
+
+    Ok(transactions)
+}
+
+/// Flies a ship along a flight plan and docks at its end
+///
+/// Each leg is flown in its planned flight mode, filling the tank first where
+/// the plan says so. `ship` is kept up to date with the nav and fuel the
+/// server reports.
+async fn fly_plan(
+    client: &ApiClient,
+    storage: &SharedStatusStorage,
+    ship: &mut Ship,
+    plan: &FlightPlan
+) -> Result<(), SpaceTradersError> {
+    for leg in &plan.legs {
+        if leg.refuel_before {
+            if ship.nav.status != ShipNavStatus::Docked {
+                ship.nav = dock_ship(client, storage, &ship.symbol).await?.nav;
+            }
+            ship.fuel = refuel_ship(client, storage, &ship.symbol).await?.fuel;
+        }
+        if ship.nav.status == ShipNavStatus::Docked {
+            ship.nav = orbit_ship(client, storage, &ship.symbol).await?.nav;
+        }
+        if ship.nav.flight_mode != leg.mode {
+            ship.nav = set_flight_mode(client, storage, &ship.symbol, leg.mode).await?.nav;
+        }
+
+        let update = navigate_ship(client, storage, &ship.symbol, &leg.to).await?;
+        ship.fuel = update
+            .fuel
+            .clone()
+            .unwrap_or_else(|| ShipFuel { current: ship.fuel.current - leg.fuel, ..ship.fuel.clone() });
+        sleep_until_arrival(&update.nav.route.arrival).await;
+        ship.nav = update.nav;
+        ship.nav.status = ShipNavStatus::InOrbit;
+    }
+
+    if ship.nav.status != ShipNavStatus::Docked {
+        ship.nav = dock_ship(client, storage, &ship.symbol).await?.nav;
+    }
+    Ok(())
+}
+
+/// Sleeps until a Unix time, if it is in the future
+///
+/// The time is cut to whole seconds, so this sleeps one second longer to not
+/// wake before it.
+async fn sleep_until(unix_time: Option<u64>) {
+    let remaining = unix_time.unwrap_or(0).saturating_sub(unix_now());
+    if remaining > 0 {
+        tokio::time::sleep(Duration::from_secs(remaining + 1)).await;
+    }
+}
+
+/// Sleeps until a route's arrival timestamp, keeping its fractional second
+async fn sleep_until_arrival(arrival: &str) {
+    let remaining = time_until(arrival);
+    if !remaining.is_zero() {
+        tokio::time::sleep(remaining).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::markets::MarketObservation;
+    use crate::mock_server::{
+        MockResponse, MockServer, market, market_response, nav_response, refuel_response, ship_json, trade_good,
+        trade_response,
+    };
+    use crate::models::Market;
+    use serde_json::json;
+
+    const SOURCE: &str = "X1-DF55-20250Z";
+    const DESTINATION: &str = "X1-DF55-B";
+    const ARRIVED: &str = "2020-01-01T00:00:00.000Z";
+
+    /// Records a market's trade goods in the price history
+    async fn observed(storage: &SharedStatusStorage, market: Market, observed_at: u64) {
+        storage.record_prices(&MarketObservation { market, observed_at }).await;
+    }
+
+    /// Storage knowing GOLD is cheap at the source and dear at the destination
+    async fn storage() -> (SharedStatusStorage, Vec<Waypoint>) {
+        let storage = SharedStatusStorage::default();
+        observed(&storage, market(SOURCE, vec![trade_good("GOLD", 10, 8, 5)]), 100).await;
+        observed(&storage, market(DESTINATION, vec![trade_good("GOLD", 60, 50, 5)]), 100).await;
+        let waypoints = vec![Waypoint::new(SOURCE.to_string(), 10, 20), Waypoint::new(DESTINATION.to_string(), 13, 24)];
+        (storage, waypoints)
+    }
+
+    #[tokio::test]
+    async fn test_trader_buys_flies_and_sells() {
+        let mut arrived = ship_json("SHIP-1");
+        arrived["nav"]["waypointSymbol"] = json!(DESTINATION);
+        arrived["cargo"] = json!({"capacity": 15, "units": 15, "inventory": [
+            {"symbol": "IRON_ORE", "name": "Iron Ore", "description": "", "units": 10},
+            {"symbol": "GOLD", "name": "Gold", "description": "", "units": 5}
+        ]});
+
+        let server = MockServer::start().await;
+        server.route("GET", "/my/ships/SHIP-1", vec![
+            MockResponse::json(200, json!({"data": ship_json("SHIP-1")})),
+            MockResponse::json(200, json!({"data": arrived})),
+        ]);
+        server.route("GET", &format!("/systems/X1-DF55/waypoints/{}/market", SOURCE), vec![
+            market_response(&market(SOURCE, vec![trade_good("GOLD", 10, 8, 5)])),
+        ]);
+        server.route("GET", &format!("/systems/X1-DF55/waypoints/{}/market", DESTINATION), vec![
+            market_response(&market(DESTINATION, vec![trade_good("GOLD", 60, 50, 5)])),
+        ]);
+        server.route("POST", "/my/ships/SHIP-1/purchase", vec![
+            trade_response("PURCHASE", "GOLD", 5, 10, &[("IRON_ORE", 10), ("GOLD", 5)], 1000),
+        ]);
+        server.route("POST", "/my/ships/SHIP-1/refuel", vec![refuel_response(400)]);
+        server.route("POST", "/my/ships/SHIP-1/orbit", vec![nav_response("IN_ORBIT", SOURCE, ARRIVED)]);
+        server.route("PATCH", "/my/ships/SHIP-1/nav", vec![nav_response("IN_ORBIT", SOURCE, ARRIVED)]);
+        server.route("POST", "/my/ships/SHIP-1/navigate", vec![nav_response("IN_TRANSIT", DESTINATION, ARRIVED)]);
+        server.route("POST", "/my/ships/SHIP-1/dock", vec![nav_response("DOCKED", DESTINATION, ARRIVED)]);
+        server.route("POST", "/my/ships/SHIP-1/sell", vec![
+            trade_response("SELL", "GOLD", 5, 50, &[("IRON_ORE", 10)], 1250),
+        ]);
+
+        let client = ApiClient::with_base_url(server.base_url(), Some("test_token"));
+        let (storage, waypoints) = storage().await;
+        observed(&storage, market(SOURCE, vec![trade_good("FUEL", 60, 55, 100)]), 100).await;
+        let outcomes = run_trader(&client, &storage, "SHIP-1", &waypoints, &TraderOptions::default(), 1).await.unwrap();
+
+        let TradeOutcome::Completed { route, transactions } = &outcomes[0] else {
+            panic!("expected a completed trip, got {:?}", outcomes[0]);
+        };
+        assert_eq!((route.buy_at.as_str(), route.sell_at.as_str(), route.units), (SOURCE, DESTINATION, 5));
+        assert_eq!(transactions.len(), 2);
+
+        // The tank is topped up at the source, then the short hop is burnt
+        let requests: Vec<_> = server.requests().into_iter().filter(|r| r.method != "GET").collect();
+        let paths: Vec<_> = requests.iter().map(|r| r.path.as_str()).collect();
+        assert_eq!(paths, [
+            "/my/ships/SHIP-1/purchase",
+            "/my/ships/SHIP-1/refuel",
+            "/my/ships/SHIP-1/orbit",
+            "/my/ships/SHIP-1/nav",
+            "/my/ships/SHIP-1/navigate",
+            "/my/ships/SHIP-1/dock",
+            "/my/ships/SHIP-1/sell",
+        ]);
+        assert_eq!(requests[3].body, r#"{"flightMode":"BURN"}"#);
+        assert_eq!(storage.read().await.transactions().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_trader_sells_leftover_cargo_before_planning() {
+        let mut emptied = ship_json("SHIP-1");
+        emptied["cargo"] = json!({"capacity": 15, "units": 0, "inventory": []});
+
+        let server = MockServer::start().await;
+        server.route("GET", "/my/ships/SHIP-1", vec![
+            MockResponse::json(200, json!({"data": ship_json("SHIP-1")})),
+            MockResponse::json(200, json!({"data": emptied})),
+        ]);
+        server.route("GET", &format!("/systems/X1-DF55/waypoints/{}/market", SOURCE), vec![
+            market_response(&market(SOURCE, vec![trade_good("IRON_ORE", 40, 30, 10)])),
+        ]);
+        server.route("POST", "/my/ships/SHIP-1/sell", vec![trade_response("SELL", "IRON_ORE", 10, 30, &[], 1300)]);
+
+        let client = ApiClient::with_base_url(server.base_url(), Some("test_token"));
+        let storage = SharedStatusStorage::default();
+        observed(&storage, market(SOURCE, vec![trade_good("IRON_ORE", 40, 30, 10)]), 100).await;
+        observed(&storage, market(DESTINATION, vec![trade_good("IRON_ORE", 30, 20, 10)]), 100).await;
+        let waypoints = vec![Waypoint::new(SOURCE.to_string(), 10, 20), Waypoint::new(DESTINATION.to_string(), 13, 24)];
+
+        let outcome = trade_once(&client, &storage, "SHIP-1", &waypoints, &TraderOptions::default()).await.unwrap();
+
+        // The ore from the last trip is sold where it pays most; buying it back elsewhere makes no profit
+        assert!(matches!(outcome, TradeOutcome::NoRoute));
+        let sells: Vec<_> = server.requests().into_iter().filter(|r| r.method == "POST").collect();
+        assert_eq!(sells.len(), 1);
+        assert_eq!(sells[0].body, r#"{"symbol":"IRON_ORE","units":10}"#);
+        assert_eq!(storage.read().await.transactions().len(), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_trader_aborts_when_destination_price_dropped_on_the_way() {
+        let mut elsewhere = ship_json("SHIP-1");
+        elsewhere["nav"]["waypointSymbol"] = json!(DESTINATION);
+        let arrival = (chrono::Utc::now() + chrono::Duration::minutes(1)).to_rfc3339();
+
+        let server = MockServer::start().await;
+        server.route("GET", "/my/ships/SHIP-1", vec![MockResponse::json(200, json!({"data": elsewhere}))]);
+        server.route("POST", "/my/ships/SHIP-1/orbit", vec![nav_response("IN_ORBIT", DESTINATION, ARRIVED)]);
+        server.route("PATCH", "/my/ships/SHIP-1/nav", vec![nav_response("IN_ORBIT", DESTINATION, ARRIVED)]);
+        server.route("POST", "/my/ships/SHIP-1/navigate", vec![nav_response("IN_TRANSIT", SOURCE, &arrival)]);
+        server.route("POST", "/my/ships/SHIP-1/dock", vec![nav_response("DOCKED", SOURCE, ARRIVED)]);
+
+        let client = ApiClient::with_base_url(server.base_url(), Some("test_token"));
+        let (storage, waypoints) = storage().await;
+
+        // Another ship sees the destination's price collapse while this one flies to the source;
+        // the paused clock only lets the flight end once that has been recorded
+        let mut changes = storage.subscribe();
+        let other_ship = storage.clone();
+        tokio::spawn(async move {
+            while let Ok(status) = changes.recv().await {
+                if status.status_type == ShipStatusType::Traveling {
+                    observed(&other_ship, market(DESTINATION, vec![trade_good("GOLD", 30, 20, 5)]), 200).await;
+                    break;
+                }
+            }
+        });
+
+        let outcome = trade_once(&client, &storage, "SHIP-1", &waypoints, &TraderOptions::default()).await.unwrap();
+
+        let TradeOutcome::Aborted { transactions, reason, .. } = outcome else {
+            panic!("expected an aborted trip, got {:?}", outcome);
+        };
+        assert!(transactions.is_empty());
+        assert!(reason.contains("fell to 20"), "{}", reason);
+        assert!(server.requests().iter().all(|r| !r.path.ends_with("/purchase")));
+    }
+
+    #[tokio::test]
+    async fn test_purchases_follow_planned_batches() {
+        let server = MockServer::start().await;
+        server.route("GET", &format!("/systems/X1-DF55/waypoints/{}/market", SOURCE), vec![
+            market_response(&market(SOURCE, vec![trade_good("GOLD", 10, 8, 5)])),
+        ]);
+        server.route("POST", "/my/ships/SHIP-1/purchase", vec![
+            trade_response("PURCHASE", "GOLD", 2, 10, &[("IRON_ORE", 10), ("GOLD", 2)], 1000),
+        ]);
+
+        let client = ApiClient::with_base_url(server.base_url(), Some("test_token"));
+        let (storage, waypoints) = storage().await;
+        // The destination only takes two units per order, so the source is bought in twos as well
+        observed(&storage, market(DESTINATION, vec![trade_good("GOLD", 60, 50, 2)]), 200).await;
+
+        let profile = ShipProfile { cargo_capacity: 5, fuel_capacity: 400, speed: 10 };
+        let route = find_trade_routes(storage.read().await.price_history(), &waypoints, SOURCE, &profile, &RouteOptions::default())
+            .remove(0);
+        let transactions = buy_leg(&client, &storage, "SHIP-1", &route, &TraderOptions::default()).await.unwrap();
+
+        assert_eq!(transactions.len(), 3);
+        let units: Vec<i64> = server
+            .requests()
+            .iter()
+            .filter(|r| r.method == "POST")
+            .map(|r| serde_json::from_str::<serde_json::Value>(&r.body).unwrap()["units"].as_i64().unwrap())
+            .collect();
+        assert_eq!(units, [2, 2, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_purchases_stop_when_credits_run_out() {
+        let server = MockServer::start().await;
+        server.route("GET", &format!("/systems/X1-DF55/waypoints/{}/market", SOURCE), vec![
+            market_response(&market(SOURCE, vec![trade_good("GOLD", 10, 8, 2)])),
+        ]);
+        server.route("POST", "/my/ships/SHIP-1/purchase", vec![
+            trade_response("PURCHASE", "GOLD", 2, 10, &[("IRON_ORE", 10), ("GOLD", 2)], 20),
+            MockResponse::json(400, json!({"error": {"code": 4600, "message": "Agent has insufficient credits"}})),
+        ]);
+
+        let client = ApiClient::with_base_url(server.base_url(), Some("test_token"));
+        let (storage, waypoints) = storage().await;
+        observed(&storage, market(DESTINATION, vec![trade_good("GOLD", 60, 50, 2)]), 200).await;
+        let profile = ShipProfile { cargo_capacity: 5, fuel_capacity: 400, speed: 10 };
+        let route = find_trade_routes(storage.read().await.price_history(), &waypoints, SOURCE, &profile, &RouteOptions::default())
+            .remove(0);
+        let transactions = buy_leg(&client, &storage, "SHIP-1", &route, &TraderOptions::default()).await.unwrap();
+
+        assert_eq!(transactions.len(), 1);
+        assert_eq!(server.requests().iter().filter(|r| r.method == "POST").count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_trader_aborts_when_purchase_price_moved() {
+        let server = MockServer::start().await;
+        server.route("GET", "/my/ships/SHIP-1", vec![MockResponse::json(200, json!({"data": ship_json("SHIP-1")}))]);
+        server.route("GET", &format!("/systems/X1-DF55/waypoints/{}/market", SOURCE), vec![
+            market_response(&market(SOURCE, vec![trade_good("GOLD", 20, 18, 5)])),
+        ]);
+
+        let client = ApiClient::with_base_url(server.base_url(), Some("test_token"));
+        let (storage, waypoints) = storage().await;
+        let outcome = trade_once(&client, &storage, "SHIP-1", &waypoints, &TraderOptions::default()).await.unwrap();
+
+        assert!(matches!(outcome, TradeOutcome::Aborted { ref transactions, .. } if transactions.is_empty()));
+        assert!(server.requests().iter().all(|r| r.method == "GET"));
+        assert_eq!(storage.read().await.price_history().latest(SOURCE, "GOLD").unwrap().purchase_price, 20);
+    }
+}
@@ -0,0 +1,162 @@
+//! Buying and selling cargo at markets
+
+use serde::Serialize;
+
+use crate::client::ApiClient;
+use crate::error::SpaceTradersError;
+use crate::markets::{SellPrices, observe_market};
+use crate::models::{ApiResponse, MarketTransaction, RefuelResult, Ship, TradeResult};
+use crate::shared_storage::SharedStatusStorage;
+
+/// Body of the purchase and sell endpoints
+#[derive(Debug, Serialize)]
+struct TradeRequest<'a> {
+    symbol: &'a str,
+    units: i32,
+}
+
+/// Buys goods at the market the ship is docked at
+///
+/// A single order may not exceed the good's trade volume.
+pub async fn purchase_cargo(
+    client: &ApiClient,
+    ship_symbol: &str,
+    trade_symbol: &str,
+    units: i32
+) -> Result<TradeResult, SpaceTradersError> {
+    let body = TradeRequest { symbol: trade_symbol, units };
+    let response: ApiResponse<TradeResult> = client
+        .post_json(&format!("/my/ships/{}/purchase", ship_symbol), &body)
+        .await?;
+    Ok(response.data)
+}
+
+/// Sells goods at the market the ship is docked at
+///
+/// A single order may not exceed the good's trade volume.
+pub async fn sell_cargo(
+    client: &ApiClient,
+    ship_symbol: &str,
+    trade_symbol: &str,
+    units: i32
+) -> Result<TradeResult, SpaceTradersError> {
+    let body = TradeRequest { symbol: trade_symbol, units };
+    let response: ApiResponse<TradeResult> = client
+        .post_json(&format!("/my/ships/{}/sell", ship_symbol), &body)
+        .await?;
+    Ok(response.data)
+}
+
+/// Fills a docked ship's fuel tank at its market and records the purchase
+pub async fn refuel_ship(
+    client: &ApiClient,
+    storage: &SharedStatusStorage,
+    ship_symbol: &str
+) -> Result<RefuelResult, SpaceTradersError> {
+    let response: ApiResponse<RefuelResult> = client.post(&format!("/my/ships/{}/refuel", ship_symbol)).await?;
+    storage.record_refuel(&response.data).await;
+
+    let transaction = &response.data.transaction;
+    println!(
+        "{} refuelled {} units for {} credits",
+        ship_symbol, transaction.units, transaction.total_price
+    );
+    Ok(response.data)
+}
+
+/// Sells everything in a docked ship's hold that its market trades
+///
+/// Each good is sold in orders of at most its trade volume. The market is
+/// re-read after every order, since selling lowers the price, and selling a
+/// good stops once its price falls below its entry in `floors`; goods without
+/// a floor are sold at any price. Every trade is recorded in the storage.
+pub async fn sell_all_cargo(
+    client: &ApiClient,
+    storage: &SharedStatusStorage,
+    ship: &Ship,
+    floors: &SellPrices
+) -> Result<Vec<MarketTransaction>, SpaceTradersError> {
+    let waypoint_symbol = &ship.nav.waypoint_symbol;
+    let mut market = observe_market(client, storage, waypoint_symbol).await?.market;
+    let mut transactions = Vec::new();
+
+    for item in &ship.cargo.inventory {
+        let floor = floors.get(&item.symbol).copied().unwrap_or(0);
+        let mut remaining = item.units;
+
+        while remaining > 0 {
+            let Some(good) = market.trade_goods.iter().flatten().find(|g| g.symbol == item.symbol) else {
+                break;
+            };
+            if good.sell_price < floor {
+                println!(
+                    "Keeping {} {}: {} pays {}, below the floor of {}",
+                    remaining, item.symbol, waypoint_symbol, good.sell_price, floor
+                );
+                break;
+            }
+
+            let units = remaining.min(good.trade_volume.max(1));
+            let result = sell_cargo(client, &ship.symbol, &item.symbol, units).await?;
+            storage.record_trade(&result).await;
+
+            let transaction = result.transaction;
+            println!(
+                "{} sold {} {} for {} ({} each)",
+                ship.symbol, transaction.units, transaction.trade_symbol, transaction.total_price, transaction.price_per_unit
+            );
+            remaining -= transaction.units.max(1);
+            transactions.push(transaction);
+
+            if remaining > 0 {
+                market = observe_market(client, storage, waypoint_symbol).await?.market;
+            }
+        }
+    }
+
+    Ok(transactions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_server::{
+        MockResponse, MockServer, market, market_response, ship_json, trade_good, trade_response,
+    };
+    use serde_json::json;
+
+    fn iron_market(sell_price: i64) -> MockResponse {
+        market_response(&market("X1-DF55-20250Z", vec![trade_good("IRON_ORE", sell_price + 10, sell_price, 4)]))
+    }
+
+    fn sale(price_per_unit: i64, cargo_units: i32, credits: i64) -> MockResponse {
+        trade_response("SELL", "IRON_ORE", 4, price_per_unit, &[("IRON_ORE", cargo_units)], credits)
+    }
+
+    #[tokio::test]
+    async fn test_sell_all_cargo_batches_until_price_floor() {
+        let server = MockServer::start().await;
+        server.route("GET", "/systems/X1-DF55/waypoints/X1-DF55-20250Z/market", vec![iron_market(50), iron_market(45), iron_market(30)]);
+        server.route("POST", "/my/ships/SHIP-1/sell", vec![sale(50, 6, 1200), sale(45, 2, 1380)]);
+
+        let client = ApiClient::with_base_url(server.base_url(), Some("test_token"));
+        let ship: Ship = serde_json::from_value(ship_json("SHIP-1")).unwrap();
+        let storage = SharedStatusStorage::default();
+        storage.update_from_ship(&ship).await;
+
+        let floors = SellPrices::from([("IRON_ORE".to_string(), 40)]);
+        let transactions = sell_all_cargo(&client, &storage, &ship, &floors).await.unwrap();
+
+        assert_eq!(transactions.len(), 2);
+        assert_eq!(transactions[1].price_per_unit, 45);
+
+        let sells: Vec<_> = server.requests().into_iter().filter(|r| r.method == "POST").collect();
+        let order: serde_json::Value = serde_json::from_str(&sells[0].body).unwrap();
+        assert_eq!(order, json!({"symbol": "IRON_ORE", "units": 4}));
+
+        let storage = storage.read().await;
+        assert_eq!(storage.credits(), Some(1380));
+        assert_eq!(storage.transactions().len(), 2);
+        assert_eq!(storage.get_status("SHIP-1").unwrap().cargo[0].units, 2);
+    }
+}
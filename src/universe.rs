@@ -0,0 +1,212 @@
+//! Persistent cache of the static universe: systems and their waypoints
+//!
+//! Systems, waypoint coordinates, traits, orbitals and modifiers only change
+//! when the server resets, so they are charted once per reset and kept on disk.
+//! The cache remembers the reset date it was built for and empties itself when
+//! the server reports a newer one.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::client::ApiClient;
+use crate::error::SpaceTradersError;
+use crate::models::{ServerStatus, System, Waypoint, system_symbol_of};
+use crate::status_storage::write_atomically;
+use crate::systems::{get_system, list_waypoints};
+
+/// Systems and waypoints charted since the last server reset
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UniverseCache {
+    reset_date: String,
+    systems: HashMap<String, System>,
+    waypoints: HashMap<String, Vec<Waypoint>>, // Keyed by system symbol
+}
+
+impl UniverseCache {
+    /// Creates an empty cache for a reset date
+    pub fn new(reset_date: &str) -> Self {
+        Self {
+            reset_date: reset_date.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Reset date the cached data belongs to; empty if it was never checked
+    pub fn reset_date(&self) -> &str {
+        &self.reset_date
+    }
+
+    /// Empties the cache if `reset_date` differs from the cached one
+    ///
+    /// Returns whether the cache was invalidated.
+    pub fn ensure_reset_date(&mut self, reset_date: &str) -> bool {
+        if self.reset_date == reset_date {
+            return false;
+        }
+        *self = Self::new(reset_date);
+        true
+    }
+
+    /// Gets a cached system
+    pub fn system(&self, system_symbol: &str) -> Option<&System> {
+        self.systems.get(system_symbol)
+    }
+
+    /// Gets every waypoint of a charted system
+    pub fn waypoints(&self, system_symbol: &str) -> Option<&[Waypoint]> {
+        self.waypoints.get(system_symbol).map(Vec::as_slice)
+    }
+
+    /// Gets a single waypoint of a charted system
+    pub fn waypoint(&self, waypoint_symbol: &str) -> Option<&Waypoint> {
+        self.waypoints(&system_symbol_of(waypoint_symbol))?
+            .iter()
+            .find(|w| w.symbol == waypoint_symbol)
+    }
+
+    /// Whether every waypoint of a system is cached
+    pub fn is_charted(&self, system_symbol: &str) -> bool {
+        self.waypoints.contains_key(system_symbol)
+    }
+
+    /// Caches a system
+    pub fn insert_system(&mut self, system: System) {
+        self.systems.insert(system.symbol.clone(), system);
+    }
+
+    /// Caches the complete waypoint list of a system
+    pub fn insert_waypoints(&mut self, system_symbol: &str, waypoints: Vec<Waypoint>) {
+        self.waypoints.insert(system_symbol.to_string(), waypoints);
+    }
+
+    /// Number of cached systems
+    pub fn len(&self) -> usize {
+        self.systems.len()
+    }
+
+    /// Whether no system is cached
+    pub fn is_empty(&self) -> bool {
+        self.systems.is_empty()
+    }
+
+    /// Writes the cache to `path`, replacing the previous file atomically
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), SpaceTradersError> {
+        write_atomically(path.as_ref(), &serde_json::to_vec(self)?)?;
+        Ok(())
+    }
+
+    /// Loads a cache written by `save`; a missing file yields an empty cache
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SpaceTradersError> {
+        match fs::read(path.as_ref()) {
+            Ok(json) => Ok(serde_json::from_slice(&json)?),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Gets the server status, including the date of the last reset
+pub async fn get_server_status(client: &ApiClient) -> Result<ServerStatus, SpaceTradersError> {
+    client.get("/").await
+}
+
+/// Checks the server's reset date and empties the cache if the universe was reset
+///
+/// Returns whether the cache was invalidated.
+pub async fn sync_reset_date(
+    client: &ApiClient,
+    universe: &mut UniverseCache
+) -> Result<bool, SpaceTradersError> {
+    let status = get_server_status(client).await?;
+    Ok(universe.ensure_reset_date(&status.reset_date))
+}
+
+/// Fetches a system and every one of its waypoints into the cache
+pub async fn chart_system(
+    client: &ApiClient,
+    universe: &mut UniverseCache,
+    system_symbol: &str
+) -> Result<(), SpaceTradersError> {
+    let system = get_system(client, system_symbol).await?;
+    let waypoints = list_waypoints(client, system_symbol).await?;
+
+    universe.insert_system(system);
+    universe.insert_waypoints(system_symbol, waypoints);
+    Ok(())
+}
+
+/// Gets every waypoint of a system, charting it first if it is not cached
+pub async fn system_waypoints<'a>(
+    client: &ApiClient,
+    universe: &'a mut UniverseCache,
+    system_symbol: &str
+) -> Result<&'a [Waypoint], SpaceTradersError> {
+    if !universe.is_charted(system_symbol) {
+        chart_system(client, universe, system_symbol).await?;
+    }
+    Ok(universe.waypoints(system_symbol).unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_server::{MockResponse, MockServer};
+    use serde_json::json;
+
+    fn mock_universe(server: &MockServer, reset_date: &str) {
+        server.route("GET", "/", vec![MockResponse::json(200, json!({
+            "status": "SpaceTraders is currently online",
+            "version": "v2.3.0",
+            "resetDate": reset_date,
+            "description": ""
+        }))]);
+        server.route("GET", "/systems/X1-DF55", vec![MockResponse::json(200, json!({
+            "data": {"symbol": "X1-DF55", "sectorSymbol": "X1", "type": "RED_STAR", "x": 1, "y": 2}
+        }))]);
+        server.route("GET", "/systems/X1-DF55/waypoints", vec![MockResponse::json(200, json!({
+            "data": [{
+                "symbol": "X1-DF55-A1",
+                "type": "ASTEROID",
+                "systemSymbol": "X1-DF55",
+                "x": 10,
+                "y": -4,
+                "orbitals": [{"symbol": "X1-DF55-A2"}],
+                "traits": [{"symbol": "COMMON_METAL_DEPOSITS", "name": "Common Metal Deposits", "description": ""}],
+                "modifiers": [{"symbol": "UNSTABLE", "name": "Unstable", "description": ""}]
+            }],
+            "meta": {"total": 1, "page": 1, "limit": 20}
+        }))]);
+    }
+
+    #[tokio::test]
+    async fn test_charted_system_survives_restart_until_reset() {
+        let path = std::env::temp_dir().join(format!("universe_{}_reset.json", std::process::id()));
+        let server = MockServer::start().await;
+        mock_universe(&server, "2026-10-11");
+        let client = ApiClient::with_base_url(server.base_url(), Some("test_token"));
+
+        let mut universe = UniverseCache::default();
+        assert!(sync_reset_date(&client, &mut universe).await.unwrap());
+        assert_eq!(system_waypoints(&client, &mut universe, "X1-DF55").await.unwrap().len(), 1);
+        universe.save(&path).unwrap();
+        let charting_requests = server.requests().len();
+
+        let mut universe = UniverseCache::load(&path).unwrap();
+        assert!(!sync_reset_date(&client, &mut universe).await.unwrap());
+        let waypoint = universe.waypoint("X1-DF55-A1").unwrap();
+        assert_eq!(waypoint.orbitals[0].symbol, "X1-DF55-A2");
+        assert_eq!(waypoint.modifiers[0].symbol, "UNSTABLE");
+        system_waypoints(&client, &mut universe, "X1-DF55").await.unwrap();
+        assert_eq!(server.requests().len(), charting_requests + 1);
+
+        assert!(universe.ensure_reset_date("2026-10-25"));
+        fs::remove_file(&path).unwrap();
+        assert!(universe.is_empty());
+        assert!(!universe.is_charted("X1-DF55"));
+    }
+}